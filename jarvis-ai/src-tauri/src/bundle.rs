@@ -0,0 +1,87 @@
+use std::fs::File;
+use std::io::{Seek, Write};
+use std::path::Path;
+use serde::Serialize;
+use zip::result::ZipResult;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::error::JarvisError;
+
+// 支持包中的单个文件
+pub struct BundleEntry {
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+impl BundleEntry {
+    pub fn new(name: &str, content: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+// 将支持包中的结构化内容序列化为格式化的JSON
+pub fn to_json<T: Serialize>(value: &T) -> Result<String, JarvisError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| JarvisError::Internal(format!("序列化支持包内容失败: {}", e)))
+}
+
+// 写入zip格式的支持包，文件名按UTF-8记录，内容以deflate压缩
+pub fn write_zip(path: &Path, entries: &[BundleEntry]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("创建{}失败: {}", path.display(), e))?;
+    write_entries(file, entries).map_err(|e| format!("写入{}失败: {}", path.display(), e))
+}
+
+fn write_entries<W: Write + Seek>(writer: W, entries: &[BundleEntry]) -> ZipResult<()> {
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for entry in entries {
+        zip.start_file(entry.name.as_str(), options)?;
+        zip.write_all(&entry.content)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    
+    // 写入的支持包能被标准zip读取，中文文件名和内容保持不变
+    #[test]
+    fn written_bundle_can_be_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("支持包.zip");
+        let entries = [
+            BundleEntry::new("logs.txt", "ERROR:root:语音识别服务初始化失败\n".repeat(100)),
+            BundleEntry::new("诊断/status.json", r#"{"status":"正常"}"#),
+            BundleEntry::new("empty.txt", ""),
+        ];
+        write_zip(&path, &entries).unwrap();
+        
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(archive.len(), entries.len());
+        for entry in &entries {
+            let mut file = archive.by_name(&entry.name).unwrap();
+            let mut content = Vec::new();
+            file.read_to_end(&mut content).unwrap();
+            assert_eq!(content, entry.content, "{}", entry.name);
+        }
+        
+        // 重复内容较多的日志应被压缩
+        let logs = archive.by_name("logs.txt").unwrap();
+        assert!(logs.compressed_size() < logs.size());
+    }
+    
+    #[test]
+    fn reports_unwritable_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("不存在的目录").join("bundle.zip");
+        let error = write_zip(&path, &[BundleEntry::new("a.txt", "a")]).unwrap_err();
+        assert!(error.contains("创建"));
+    }
+}
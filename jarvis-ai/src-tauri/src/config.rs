@@ -0,0 +1,348 @@
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::process::{validate_launch_command, RestartPolicy};
+use crate::{
+    integrity, lock, normalize_remote_url, validate_http_path, validate_port, AppState,
+    ReloadMechanism, DEFAULT_BIND_ADDRESS,
+    DEFAULT_HEALTH_PATH, DEFAULT_HTTP_RETRIES, DEFAULT_INSTANCE, DEFAULT_JARVIS_PORT,
+    DEFAULT_MAX_CONCURRENT_REQUESTS, DEFAULT_READINESS_PATH, DEFAULT_READY_TIMEOUT_SECS,
+    DEFAULT_SHUTDOWN_GRACE_SECS, DEFAULT_STALL_THRESHOLD_SECS, DEFAULT_STATUS_POLL_INTERVAL_SECS,
+    MAX_CONCURRENT_REQUESTS_LIMIT, MAX_SHUTDOWN_GRACE_SECS,
+};
+
+// 配置文件名，位于应用配置目录下
+const CONFIG_FILE_NAME: &str = "jarvis.toml";
+
+// 用户最近一次运行意图的记录文件及其内容
+const INTENT_FILE_NAME: &str = "last_intent";
+const INTENT_RUNNING: &str = "running";
+const INTENT_STOPPED: &str = "stopped";
+
+// 持久化的服务配置，环境变量可能包含密钥，不写入配置文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JarvisConfig {
+    pub port: u16,
+    // 默认实例的监听地址，非回环地址时局域网内的设备也可访问
+    pub bind_address: String,
+    pub python_path: Option<String>,
+    pub core_script_path: Option<PathBuf>,
+    // 核心脚本期望的SHA-256，设置后启动前校验
+    pub expected_core_hash: Option<String>,
+    pub venv_path: Option<PathBuf>,
+    pub status_poll_interval_secs: u64,
+    pub health_path: String,
+    pub readiness_path: String,
+    pub auto_start: bool,
+    // 以独立模式启动核心，关闭应用后核心继续运行，下次启动时重新连接
+    pub detached: bool,
+    pub shutdown_grace_secs: u64,
+    pub ready_timeout_secs: u64,
+    pub startup_delay_ms: u64,
+    // 超过该时间(秒)没有成功的健康检查即视为服务卡死
+    pub stall_threshold_secs: u64,
+    // 状态检查和获取模型列表遇到连接错误时的重试次数
+    pub http_retries: u32,
+    // 自动重启策略，见RestartPolicy
+    pub max_restarts: u32,
+    pub restart_window_secs: u64,
+    pub backoff_base_ms: u64,
+    // 同时转发给核心的请求数上限
+    pub max_concurrent_requests: usize,
+    // 重新加载核心的方式(signal或endpoint)
+    pub reload_mechanism: ReloadMechanism,
+    // 自定义启动命令模板，支持{script}和{port}占位符
+    pub launch_command: Option<Vec<String>>,
+    // 默认实例的远程服务地址，未设置时为本地模式
+    pub remote_base_url: Option<String>,
+}
+
+impl Default for JarvisConfig {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_JARVIS_PORT,
+            bind_address: DEFAULT_BIND_ADDRESS.to_string(),
+            python_path: None,
+            core_script_path: None,
+            expected_core_hash: None,
+            venv_path: None,
+            status_poll_interval_secs: DEFAULT_STATUS_POLL_INTERVAL_SECS,
+            health_path: DEFAULT_HEALTH_PATH.to_string(),
+            readiness_path: DEFAULT_READINESS_PATH.to_string(),
+            auto_start: true,
+            detached: false,
+            shutdown_grace_secs: DEFAULT_SHUTDOWN_GRACE_SECS,
+            ready_timeout_secs: DEFAULT_READY_TIMEOUT_SECS,
+            startup_delay_ms: 0,
+            stall_threshold_secs: DEFAULT_STALL_THRESHOLD_SECS,
+            http_retries: DEFAULT_HTTP_RETRIES,
+            max_restarts: RestartPolicy::default().max_restarts,
+            restart_window_secs: RestartPolicy::default().restart_window_secs,
+            backoff_base_ms: RestartPolicy::default().backoff_base_ms,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            reload_mechanism: ReloadMechanism::default(),
+            launch_command: None,
+            remote_base_url: None,
+        }
+    }
+}
+
+impl JarvisConfig {
+    // 从当前内存中的设置生成配置
+    pub fn from_state(app_state: &AppState) -> Self {
+        let restart_policy = *lock(&app_state.restart_policy);
+        Self {
+            port: *lock(&app_state.default_instance().port),
+            bind_address: lock(&app_state.default_instance().bind_address).to_string(),
+            python_path: lock(&app_state.python_path).clone(),
+            core_script_path: lock(&app_state.core_script_path).clone(),
+            expected_core_hash: lock(&app_state.expected_core_hash).clone(),
+            venv_path: lock(&app_state.venv_path).clone(),
+            status_poll_interval_secs: *lock(&app_state.status_poll_interval_secs),
+            health_path: lock(&app_state.health_path).clone(),
+            readiness_path: lock(&app_state.readiness_path).clone(),
+            auto_start: *lock(&app_state.auto_start),
+            detached: *lock(&app_state.detached),
+            shutdown_grace_secs: *lock(&app_state.shutdown_grace_secs),
+            ready_timeout_secs: *lock(&app_state.ready_timeout_secs),
+            startup_delay_ms: *lock(&app_state.startup_delay_ms),
+            stall_threshold_secs: *lock(&app_state.stall_threshold_secs),
+            http_retries: *lock(&app_state.http_retries),
+            max_restarts: restart_policy.max_restarts,
+            restart_window_secs: restart_policy.restart_window_secs,
+            backoff_base_ms: restart_policy.backoff_base_ms,
+            max_concurrent_requests: *lock(&app_state.max_concurrent_requests),
+            reload_mechanism: *lock(&app_state.reload_mechanism),
+            launch_command: lock(&app_state.launch_command).clone(),
+            remote_base_url: lock(&app_state.default_instance().remote_base_url).clone(),
+        }
+    }
+    
+    // 将配置写入内存状态，无效的值保留默认设置
+    pub fn apply_to(&self, app_state: &AppState) {
+        if validate_port(self.port).is_ok() {
+            *lock(&app_state.default_instance().port) = self.port;
+        }
+        if let Ok(address) = self.bind_address.trim().parse::<IpAddr>() {
+            *lock(&app_state.default_instance().bind_address) = address;
+        }
+        if self.status_poll_interval_secs > 0 {
+            *lock(&app_state.status_poll_interval_secs) = self.status_poll_interval_secs;
+        }
+        if validate_http_path(&self.health_path).is_ok() {
+            *lock(&app_state.health_path) = self.health_path.clone();
+        }
+        if validate_http_path(&self.readiness_path).is_ok() {
+            *lock(&app_state.readiness_path) = self.readiness_path.clone();
+        }
+        *lock(&app_state.auto_start) = self.auto_start;
+        *lock(&app_state.detached) = self.detached;
+        *lock(&app_state.shutdown_grace_secs) = self.shutdown_grace_secs.min(MAX_SHUTDOWN_GRACE_SECS);
+        if self.ready_timeout_secs > 0 {
+            *lock(&app_state.ready_timeout_secs) = self.ready_timeout_secs;
+        }
+        *lock(&app_state.startup_delay_ms) = self.startup_delay_ms;
+        if self.stall_threshold_secs > 0 {
+            *lock(&app_state.stall_threshold_secs) = self.stall_threshold_secs;
+        }
+        *lock(&app_state.http_retries) = self.http_retries;
+        let mut restart_policy = lock(&app_state.restart_policy);
+        restart_policy.max_restarts = self.max_restarts;
+        if self.restart_window_secs > 0 {
+            restart_policy.restart_window_secs = self.restart_window_secs;
+        }
+        if self.backoff_base_ms > 0 {
+            restart_policy.backoff_base_ms = self.backoff_base_ms;
+        }
+        if let Some(url) = self.remote_base_url.as_deref() {
+            if let Ok(url) = normalize_remote_url(url) {
+                *lock(&app_state.default_instance().remote_base_url) = Some(url);
+            }
+        }
+        if (1..=MAX_CONCURRENT_REQUESTS_LIMIT).contains(&self.max_concurrent_requests) {
+            app_state.set_max_concurrent_requests(self.max_concurrent_requests);
+        }
+        if self.reload_mechanism == ReloadMechanism::Endpoint || cfg!(unix) {
+            *lock(&app_state.reload_mechanism) = self.reload_mechanism;
+        }
+        if let Some(template) = self.launch_command.as_deref() {
+            if validate_launch_command(template).is_ok() {
+                *lock(&app_state.launch_command) = Some(template.to_vec());
+            }
+        }
+        *lock(&app_state.python_path) = self.python_path.clone();
+        *lock(&app_state.core_script_path) = self.core_script_path.clone();
+        // 哈希格式不正确时仍保留原值，使启动时校验失败，避免配置写错后悄悄关闭校验
+        *lock(&app_state.expected_core_hash) = self.expected_core_hash.as_deref().map(|hash| {
+            integrity::normalize_hash(hash).unwrap_or_else(|e| {
+                app_state.log_app(DEFAULT_INSTANCE, e.to_string());
+                hash.to_string()
+            })
+        });
+        *lock(&app_state.venv_path) = self.venv_path.clone();
+    }
+}
+
+// 配置文件路径
+pub fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("获取应用配置目录失败: {}", e))?;
+    Ok(dir.join(CONFIG_FILE_NAME))
+}
+
+// 读取配置文件，文件不存在时返回默认配置
+pub fn load_config(app: &AppHandle) -> Result<JarvisConfig, String> {
+    load_config_from(&config_path(app)?)
+}
+
+fn load_config_from(path: &Path) -> Result<JarvisConfig, String> {
+    if !path.exists() {
+        return Ok(JarvisConfig::default());
+    }
+    
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("读取配置文件{}失败: {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("解析配置文件{}失败: {}", path.display(), e))
+}
+
+// 写入配置文件，返回配置文件路径
+pub fn write_config(app: &AppHandle, config: &JarvisConfig) -> Result<PathBuf, String> {
+    let path = config_path(app)?;
+    write_config_to(&path, config)?;
+    Ok(path)
+}
+
+fn write_config_to(path: &Path, config: &JarvisConfig) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("创建配置目录{}失败: {}", dir.display(), e))?;
+    }
+    
+    let content = toml::to_string_pretty(config).map_err(|e| format!("序列化配置失败: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("写入配置文件{}失败: {}", path.display(), e))
+}
+
+// 只修改配置文件中的部分设置，其余设置保持文件中的值
+// 配置文件无法解析时返回错误，不会用内存中的设置覆盖用户的配置文件
+pub fn update(app: &AppHandle, f: impl FnOnce(&mut JarvisConfig)) -> Result<PathBuf, String> {
+    let path = config_path(app)?;
+    update_at(&path, f)?;
+    Ok(path)
+}
+
+fn update_at(path: &Path, f: impl FnOnce(&mut JarvisConfig)) -> Result<(), String> {
+    let mut config = load_config_from(path)?;
+    f(&mut config);
+    write_config_to(path, &config)
+}
+
+// 记录用户最近一次启动/停止服务的意图，用于决定下次启动应用时是否自动启动
+pub fn save_run_intent(app: &AppHandle, running: bool) -> Result<(), String> {
+    let path = intent_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("创建配置目录{}失败: {}", dir.display(), e))?;
+    }
+    
+    let content = if running { INTENT_RUNNING } else { INTENT_STOPPED };
+    fs::write(&path, content).map_err(|e| format!("写入{}失败: {}", path.display(), e))
+}
+
+// 读取用户最近一次的意图，没有记录时视为需要运行
+pub fn load_run_intent(app: &AppHandle) -> bool {
+    match intent_path(app).and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string())) {
+        Ok(content) => content.trim() != INTENT_STOPPED,
+        Err(_) => true,
+    }
+}
+
+fn intent_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(config_path(app)?.with_file_name(INTENT_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 写入后读回的配置与原配置一致
+    #[test]
+    fn config_round_trips_through_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(CONFIG_FILE_NAME);
+        let config = JarvisConfig {
+            port: 9100,
+            bind_address: "0.0.0.0".to_string(),
+            python_path: Some("/usr/bin/python3".to_string()),
+            core_script_path: Some(PathBuf::from("/opt/jarvis/main.py")),
+            shutdown_grace_secs: 7,
+            launch_command: Some(vec!["python".to_string(), "{script}".to_string()]),
+            remote_base_url: Some("http://10.0.0.2:8000".to_string()),
+            reload_mechanism: ReloadMechanism::Endpoint,
+            ..JarvisConfig::default()
+        };
+        
+        write_config_to(&path, &config).unwrap();
+        let loaded = load_config_from(&path).unwrap();
+        
+        assert_eq!(
+            toml::to_string_pretty(&loaded).unwrap(),
+            toml::to_string_pretty(&config).unwrap()
+        );
+    }
+    
+    // 配置文件不存在时使用默认配置
+    #[test]
+    fn missing_file_loads_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded = load_config_from(&dir.path().join(CONFIG_FILE_NAME)).unwrap();
+        
+        assert_eq!(loaded.port, DEFAULT_JARVIS_PORT);
+        assert!(loaded.auto_start);
+    }
+    
+    // update只修改指定的设置，文件中的其他设置保持不变
+    #[test]
+    fn update_keeps_other_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, "port = 9200\n").unwrap();
+        
+        update_at(&path, |config| config.shutdown_grace_secs = 3).unwrap();
+        let loaded = load_config_from(&path).unwrap();
+        
+        assert_eq!(loaded.port, 9200);
+        assert_eq!(loaded.shutdown_grace_secs, 3);
+    }
+    
+    // 配置文件无法解析时update返回错误，不覆盖用户的文件
+    #[test]
+    fn update_leaves_malformed_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        let content = "port = \"不是端口\n";
+        fs::write(&path, content).unwrap();
+        
+        let result = update_at(&path, |config| config.auto_start = false);
+        
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+    
+    // 超过上限的宽限期按上限写入内存状态
+    #[test]
+    fn apply_to_clamps_shutdown_grace() {
+        let app_state = AppState::default();
+        let config = JarvisConfig {
+            shutdown_grace_secs: MAX_SHUTDOWN_GRACE_SECS + 100,
+            ..JarvisConfig::default()
+        };
+        
+        config.apply_to(&app_state);
+        
+        assert_eq!(*lock(&app_state.shutdown_grace_secs), MAX_SHUTDOWN_GRACE_SECS);
+    }
+}
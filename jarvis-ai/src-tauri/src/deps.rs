@@ -0,0 +1,416 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+
+// pip安装所处阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PipPhase {
+    Collecting,
+    Downloading,
+    Building,
+    Installing,
+    Done,
+    Other,
+}
+
+// pip-progress事件内容，percent为根据已收集的包数估算的粗略进度
+#[derive(Debug, Clone, Serialize)]
+pub struct PipProgressEvent {
+    pub line: String,
+    pub phase: PipPhase,
+    pub percent: Option<u8>,
+}
+
+// 根据pip输出的每一行跟踪安装进度
+pub struct PipProgress {
+    total: usize,
+    collected: usize,
+}
+
+impl PipProgress {
+    pub fn new(total: usize) -> Self {
+        Self { total, collected: 0 }
+    }
+    
+    pub fn update(&mut self, line: String) -> PipProgressEvent {
+        let phase = parse_pip_phase(&line);
+        if phase == PipPhase::Collecting {
+            self.collected += 1;
+        }
+        
+        // 收集阶段最多到90%，依赖的依赖也会计入，因此只是粗略估计
+        let percent = match phase {
+            PipPhase::Installing => Some(95),
+            PipPhase::Done => Some(100),
+            _ if self.total > 0 => Some((self.collected * 90 / self.total).min(90) as u8),
+            _ => None,
+        };
+        
+        PipProgressEvent { line, phase, percent }
+    }
+}
+
+// 根据pip输出行判断安装阶段
+pub fn parse_pip_phase(line: &str) -> PipPhase {
+    let line = line.trim_start();
+    if line.starts_with("Collecting ") || line.starts_with("Requirement already satisfied") {
+        PipPhase::Collecting
+    } else if line.starts_with("Downloading ") || line.starts_with("Using cached ") {
+        PipPhase::Downloading
+    } else if line.starts_with("Building ") || line.starts_with("Created wheel") {
+        PipPhase::Building
+    } else if line.starts_with("Installing collected packages") {
+        PipPhase::Installing
+    } else if line.starts_with("Successfully installed") {
+        PipPhase::Done
+    } else {
+        PipPhase::Other
+    }
+}
+
+// requirements文件中的一条依赖
+#[derive(Debug, Clone)]
+pub struct Requirement {
+    pub name: String,
+    pub specifier: String,
+}
+
+// 单个依赖包的检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct PackageStatus {
+    pub name: String,
+    pub required: String,
+    pub installed: Option<String>,
+}
+
+// 依赖检查报告
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyReport {
+    pub satisfied: Vec<PackageStatus>,
+    pub missing: Vec<PackageStatus>,
+    pub outdated: Vec<PackageStatus>,
+}
+
+// 统一包名格式(不区分大小写，-、_、.视为相同)
+pub fn normalize_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(['_', '.'], "-")
+}
+
+// 解析一行依赖声明，跳过空行、注释和pip选项
+pub fn parse_requirement(line: &str) -> Option<Requirement> {
+    let line = line.split('#').next()?.split(';').next()?.trim();
+    if line.is_empty() || line.starts_with('-') {
+        return None;
+    }
+    
+    let name_end = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(line.len());
+    let name = &line[..name_end];
+    if name.is_empty() {
+        return None;
+    }
+    
+    // 去掉extras部分，例如package[extra]>=1.0
+    let rest = line[name_end..].trim_start();
+    let rest = match rest.strip_prefix('[') {
+        Some(after) => after.split_once(']').map_or("", |(_, spec)| spec),
+        None => rest,
+    };
+    
+    Some(Requirement {
+        name: name.to_string(),
+        specifier: rest.trim().to_string(),
+    })
+}
+
+// 读取requirements文件中的全部依赖，重复声明的包只保留第一次
+pub fn read_requirements(path: &Path) -> Result<Vec<Requirement>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("读取{}失败: {}", path.display(), e))?;
+    
+    let mut seen = HashSet::new();
+    Ok(content
+        .lines()
+        .filter_map(parse_requirement)
+        .filter(|req| seen.insert(normalize_name(&req.name)))
+        .collect())
+}
+
+// 版本约束支持的比较符，较长的写在前面以便优先匹配
+const SPECIFIER_OPERATORS: &[&str] = &["===", "~=", "==", "!=", ">=", "<=", ">", "<"];
+
+// 检查单个版本约束的格式，例如">=1.0"或"==2.*"
+fn is_valid_clause(clause: &str) -> bool {
+    let Some(version) = SPECIFIER_OPERATORS
+        .iter()
+        .find_map(|op| clause.trim().strip_prefix(op))
+        .map(str::trim)
+    else {
+        return false;
+    };
+    
+    let version = version.strip_suffix(".*").unwrap_or(version);
+    version.starts_with(|c: char| c.is_ascii_digit())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '!' | '-' | '_'))
+}
+
+// 检查包名是否符合PEP 508的格式(首尾为字母或数字，中间允许-、_、.)，防止以-开头被pip当作选项
+pub fn is_valid_package_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    match (bytes.first(), bytes.last()) {
+        (Some(first), Some(last)) => {
+            first.is_ascii_alphanumeric()
+                && last.is_ascii_alphanumeric()
+                && bytes.iter().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+        }
+        _ => false,
+    }
+}
+
+// 检查精确版本号的格式，例如"1.2.3"
+pub fn is_valid_version(version: &str) -> bool {
+    !version.contains('*') && is_valid_clause(&format!("=={}", version))
+}
+
+// 找出版本约束明显无效的依赖，通过URL(@ ...)指定的依赖不做检查
+pub fn invalid_specifiers(requirements: &[Requirement]) -> Vec<String> {
+    requirements
+        .iter()
+        .filter(|req| !req.specifier.is_empty() && !req.specifier.starts_with('@'))
+        .filter(|req| !req.specifier.split(',').all(is_valid_clause))
+        .map(|req| format!("{}{}", req.name, req.specifier))
+        .collect()
+}
+
+// 解析`pip freeze`输出，返回包名到已安装版本的映射
+pub fn parse_freeze(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once("=="))
+        .map(|(name, version)| (normalize_name(name), version.trim().to_string()))
+        .collect()
+}
+
+// 将版本号拆分为数字段，例如"4.8.1.78"
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| {
+            part.chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+                .parse()
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+// 比较两个版本号，逐段按数字比较
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_parts(a), version_parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+// 检查已安装版本是否满足单个版本约束
+fn satisfies_clause(installed: &str, clause: &str) -> bool {
+    let clause = clause.trim();
+    let (op, version) = SPECIFIER_OPERATORS
+        .iter()
+        .find_map(|op| clause.strip_prefix(op).map(|v| (*op, v.trim())))
+        .unwrap_or(("==", clause));
+    
+    if let Some(prefix) = version.strip_suffix(".*") {
+        let matches = installed == prefix || installed.starts_with(&format!("{}.", prefix));
+        return if op == "!=" { !matches } else { matches };
+    }
+    
+    let ordering = compare_versions(installed, version);
+    match op {
+        "===" => installed == version,
+        "==" => ordering == Ordering::Equal,
+        "!=" => ordering != Ordering::Equal,
+        ">=" => ordering != Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        // ~=X.Y.Z 等价于 >=X.Y.Z 且X.Y部分相同
+        _ => {
+            let required = version_parts(version);
+            let prefix = &required[..required.len().saturating_sub(1)];
+            ordering != Ordering::Less && version_parts(installed).starts_with(prefix)
+        }
+    }
+}
+
+// 检查已安装版本是否满足依赖声明中的全部约束
+pub fn satisfies(installed: &str, specifier: &str) -> bool {
+    specifier
+        .split(',')
+        .filter(|clause| !clause.trim().is_empty())
+        .all(|clause| satisfies_clause(installed, clause))
+}
+
+// 对比依赖声明和已安装的包，生成检查报告
+pub fn build_report(requirements: &[Requirement], installed: &HashMap<String, String>) -> DependencyReport {
+    let mut report = DependencyReport::default();
+    
+    for req in requirements {
+        let version = installed.get(&normalize_name(&req.name)).cloned();
+        let status = PackageStatus {
+            name: req.name.clone(),
+            required: req.specifier.clone(),
+            installed: version.clone(),
+        };
+        
+        match version {
+            None => report.missing.push(status),
+            Some(v) if satisfies(&v, &req.specifier) => report.satisfied.push(status),
+            Some(_) => report.outdated.push(status),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn requirement(name: &str, specifier: &str) -> Requirement {
+        Requirement {
+            name: name.to_string(),
+            specifier: specifier.to_string(),
+        }
+    }
+    
+    // 跳过空行、注释和pip选项，去掉extras和环境标记
+    #[test]
+    fn parses_requirement_lines() {
+        let skipped = ["", "   ", "# 语音识别依赖", "-r base.txt", "--index-url https://x.org"];
+        for line in skipped {
+            assert!(parse_requirement(line).is_none(), "{}", line);
+        }
+        
+        let cases = [
+            ("requests", "requests", ""),
+            ("numpy>=1.24  # 数值计算", "numpy", ">=1.24"),
+            ("uvicorn[standard] >= 0.23, <1.0", "uvicorn", ">= 0.23, <1.0"),
+            ("pywin32==306; sys_platform == 'win32'", "pywin32", "==306"),
+            ("opencv_python.headless~=4.8.1", "opencv_python.headless", "~=4.8.1"),
+            ("mypkg @ https://example.com/mypkg.whl", "mypkg", "@ https://example.com/mypkg.whl"),
+        ];
+        for (line, name, specifier) in cases {
+            let req = parse_requirement(line).unwrap();
+            assert_eq!((req.name.as_str(), req.specifier.as_str()), (name, specifier), "{}", line);
+        }
+    }
+    
+    // 重复声明的包(包名不区分大小写和分隔符)只保留第一次
+    #[test]
+    fn reads_requirements_without_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("requirements.txt");
+        let content = "# 依赖列表\nFastAPI>=0.100\n\nfastapi==0.99\n\
+                       Speech_Recognition\nspeech-recognition>=3\n";
+        fs::write(&path, content).unwrap();
+        
+        let requirements = read_requirements(&path).unwrap();
+        let names: Vec<String> = requirements.into_iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["FastAPI", "Speech_Recognition"]);
+        assert!(read_requirements(&dir.path().join("missing.txt")).is_err());
+    }
+    
+    // 只报告明显无效的版本约束，URL依赖和不带约束的依赖不检查
+    #[test]
+    fn detects_invalid_specifiers() {
+        let requirements = [
+            requirement("requests", ""),
+            requirement("numpy", ">=1.24,<2"),
+            requirement("torch", "==2.*"),
+            requirement("mypkg", "@ https://example.com/mypkg.whl"),
+            requirement("flask", "=2.0"),
+            requirement("django", ">=abc"),
+            requirement("pillow", ">=9,"),
+        ];
+        let invalid = vec!["flask=2.0", "django>=abc", "pillow>=9,"];
+        assert_eq!(invalid_specifiers(&requirements), invalid);
+    }
+    
+    #[test]
+    fn validates_package_names_and_versions() {
+        assert!(is_valid_package_name("opencv-python"));
+        assert!(is_valid_package_name("zope.interface"));
+        assert!(!is_valid_package_name(""));
+        assert!(!is_valid_package_name("-r"));
+        assert!(!is_valid_package_name("包名"));
+        assert!(!is_valid_package_name("numpy "));
+        
+        assert!(is_valid_version("1.2.3"));
+        assert!(is_valid_version("2.0.0rc1"));
+        assert!(!is_valid_version("2.*"));
+        assert!(!is_valid_version("latest"));
+        assert!(!is_valid_version(""));
+    }
+    
+    #[test]
+    fn checks_installed_versions_against_specifiers() {
+        assert!(satisfies("1.2.3", ""));
+        assert!(satisfies("1.24.0", ">=1.24,<2"));
+        assert!(!satisfies("2.0", ">=1.24,<2"));
+        assert!(satisfies("2.1.0", "==2.*"));
+        assert!(!satisfies("20.1", "==2.*"));
+        assert!(!satisfies("2.1", "!=2.*"));
+        assert!(satisfies("4.8.5", "~=4.8.1"));
+        assert!(!satisfies("4.9.0", "~=4.8.1"));
+        assert!(satisfies("1.0", "==1.0.0"));
+        assert!(!satisfies("1.0", "===1.0.0"));
+    }
+    
+    // 根据pip freeze输出区分已满足、缺失和版本不符的依赖
+    #[test]
+    fn builds_dependency_report() {
+        let freeze = "Flask==2.3.0\nnumpy==1.21.0\n-e git+https://example.com/repo\n";
+        let installed = parse_freeze(freeze);
+        let requirements = [
+            requirement("flask", ">=2"),
+            requirement("NumPy", ">=1.24"),
+            requirement("requests", ""),
+        ];
+        let report = build_report(&requirements, &installed);
+        
+        let names = |list: &[PackageStatus]| {
+            list.iter().map(|p| p.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&report.satisfied), vec!["flask"]);
+        assert_eq!(names(&report.outdated), vec!["NumPy"]);
+        assert_eq!(names(&report.missing), vec!["requests"]);
+        assert_eq!(report.outdated[0].installed.as_deref(), Some("1.21.0"));
+    }
+    
+    // 收集阶段按已收集的包数估算进度，最多到90%
+    #[test]
+    fn tracks_pip_progress() {
+        let mut progress = PipProgress::new(2);
+        assert_eq!(progress.update("Collecting requests".to_string()).percent, Some(45));
+        let event = progress.update("  Downloading requests-2.31.0.whl (62 kB)".to_string());
+        assert_eq!((event.phase, event.percent), (PipPhase::Downloading, Some(45)));
+        progress.update("Requirement already satisfied: numpy".to_string());
+        assert_eq!(progress.update("Collecting idna".to_string()).percent, Some(90));
+        let event = progress.update("Installing collected packages: idna, requests".to_string());
+        assert_eq!((event.phase, event.percent), (PipPhase::Installing, Some(95)));
+        assert_eq!(progress.update("Successfully installed".to_string()).percent, Some(100));
+        
+        assert_eq!(PipProgress::new(0).update("WARNING: 无法连接".to_string()).percent, None);
+    }
+}
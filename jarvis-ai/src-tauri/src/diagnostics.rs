@@ -0,0 +1,37 @@
+use serde::Serialize;
+
+use crate::error::JarvisError;
+
+// 单项诊断结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticCheck {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl DiagnosticCheck {
+    // 由检查结果生成诊断项，失败时detail为错误说明
+    pub fn from_result(name: &'static str, result: Result<String, JarvisError>) -> Self {
+        match result {
+            Ok(detail) => Self { name, passed: true, detail },
+            Err(e) => Self { name, passed: false, detail: e.message().to_string() },
+        }
+    }
+}
+
+// 启动前的综合诊断报告，passed表示全部检查通过
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub passed: bool,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+impl DiagnosticsReport {
+    pub fn new(checks: Vec<DiagnosticCheck>) -> Self {
+        Self {
+            passed: checks.iter().all(|check| check.passed),
+            checks,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+// 命令返回给前端的错误，code供前端区分错误类型，message为可直接展示的说明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JarvisError {
+    PythonNotFound(String),
+    PythonVersionUnsupported(String),
+    ScriptMissing(String),
+    IntegrityCheckFailed(String),
+    RequirementsMissing(String),
+    PortInUse(String),
+    AlreadyRunning(String),
+    NotRunning(String),
+    RemoteMode(String),
+    Unauthorized(String),
+    NotSupported(String),
+    InstanceNotFound(String),
+    InvalidArgument(String),
+    Timeout(String),
+    TooBusy(String),
+    Cancelled(String),
+    Superseded(String),
+    SpawnFailed(String),
+    ProcessControlFailed(String),
+    // 核心进程意外退出
+    Crashed(String),
+    HttpError(String),
+    InvalidResponse(String),
+    VenvFailed(String),
+    PipFailed(String),
+    Io(String),
+    Internal(String),
+}
+
+impl JarvisError {
+    // 稳定的错误码，前端据此判断错误类型
+    pub fn code(&self) -> &'static str {
+        match self {
+            JarvisError::PythonNotFound(_) => "python_not_found",
+            JarvisError::PythonVersionUnsupported(_) => "python_version_unsupported",
+            JarvisError::ScriptMissing(_) => "script_missing",
+            JarvisError::IntegrityCheckFailed(_) => "integrity_check_failed",
+            JarvisError::RequirementsMissing(_) => "requirements_missing",
+            JarvisError::PortInUse(_) => "port_in_use",
+            JarvisError::AlreadyRunning(_) => "already_running",
+            JarvisError::NotRunning(_) => "not_running",
+            JarvisError::RemoteMode(_) => "remote_mode",
+            JarvisError::Unauthorized(_) => "unauthorized",
+            JarvisError::NotSupported(_) => "not_supported",
+            JarvisError::InstanceNotFound(_) => "instance_not_found",
+            JarvisError::InvalidArgument(_) => "invalid_argument",
+            JarvisError::Timeout(_) => "timeout",
+            JarvisError::TooBusy(_) => "too_busy",
+            JarvisError::Cancelled(_) => "cancelled",
+            JarvisError::Superseded(_) => "superseded",
+            JarvisError::SpawnFailed(_) => "spawn_failed",
+            JarvisError::ProcessControlFailed(_) => "process_control_failed",
+            JarvisError::Crashed(_) => "crashed",
+            JarvisError::HttpError(_) => "http_error",
+            JarvisError::InvalidResponse(_) => "invalid_response",
+            JarvisError::VenvFailed(_) => "venv_failed",
+            JarvisError::PipFailed(_) => "pip_failed",
+            JarvisError::Io(_) => "io",
+            JarvisError::Internal(_) => "internal",
+        }
+    }
+    
+    pub fn message(&self) -> &str {
+        match self {
+            JarvisError::PythonNotFound(message)
+            | JarvisError::PythonVersionUnsupported(message)
+            | JarvisError::ScriptMissing(message)
+            | JarvisError::IntegrityCheckFailed(message)
+            | JarvisError::RequirementsMissing(message)
+            | JarvisError::PortInUse(message)
+            | JarvisError::AlreadyRunning(message)
+            | JarvisError::NotRunning(message)
+            | JarvisError::RemoteMode(message)
+            | JarvisError::Unauthorized(message)
+            | JarvisError::NotSupported(message)
+            | JarvisError::InstanceNotFound(message)
+            | JarvisError::InvalidArgument(message)
+            | JarvisError::Timeout(message)
+            | JarvisError::TooBusy(message)
+            | JarvisError::Cancelled(message)
+            | JarvisError::Superseded(message)
+            | JarvisError::SpawnFailed(message)
+            | JarvisError::ProcessControlFailed(message)
+            | JarvisError::Crashed(message)
+            | JarvisError::HttpError(message)
+            | JarvisError::InvalidResponse(message)
+            | JarvisError::VenvFailed(message)
+            | JarvisError::PipFailed(message)
+            | JarvisError::Io(message)
+            | JarvisError::Internal(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for JarvisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for JarvisError {}
+
+// 序列化为{ code, message }，便于前端按code分支处理
+impl Serialize for JarvisError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("JarvisError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.end()
+    }
+}
+
+// 尚未细分类型的内部错误
+impl From<String> for JarvisError {
+    fn from(message: String) -> Self {
+        JarvisError::Internal(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    // 前端依赖该格式区分错误类型，修改时需同步前端
+    #[test]
+    fn serializes_to_code_and_message() {
+        let error = JarvisError::PortInUse("端口8000已被占用".to_string());
+        
+        assert_eq!(
+            serde_json::to_value(error).unwrap(),
+            json!({"code": "port_in_use", "message": "端口8000已被占用"})
+        );
+    }
+}
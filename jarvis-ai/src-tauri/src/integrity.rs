@@ -0,0 +1,89 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use sha2::{Digest, Sha256};
+
+use crate::error::JarvisError;
+
+// SHA-256的十六进制长度
+const SHA256_HEX_LEN: usize = 64;
+
+// 计算文件的SHA-256，返回小写十六进制字符串
+pub fn sha256_file(path: &Path) -> Result<String, JarvisError> {
+    let mut file = File::open(path)
+        .map_err(|e| JarvisError::Io(format!("打开{}失败: {}", path.display(), e)))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| JarvisError::Io(format!("读取{}失败: {}", path.display(), e)))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    
+    Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+// 校验并规范化用户提供的哈希值，允许大写和"sha256:"前缀
+pub fn normalize_hash(hash: &str) -> Result<String, JarvisError> {
+    let hash = hash.trim();
+    let hash = hash.strip_prefix("sha256:").unwrap_or(hash);
+    if hash.len() != SHA256_HEX_LEN || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(JarvisError::InvalidArgument(
+            "期望的核心脚本哈希必须是64位十六进制的SHA-256".to_string(),
+        ));
+    }
+    Ok(hash.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // "abc"的SHA-256
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    
+    #[test]
+    fn sha256_file_matches_known_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.py");
+        std::fs::write(&path, "abc").unwrap();
+        
+        assert_eq!(sha256_file(&path).unwrap(), ABC_SHA256);
+    }
+    
+    #[test]
+    fn sha256_file_reports_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = sha256_file(&dir.path().join("missing.py"));
+        
+        assert!(matches!(result, Err(JarvisError::Io(_))));
+    }
+    
+    // 大写和"sha256:"前缀都规范化为小写的十六进制
+    #[test]
+    fn normalize_hash_accepts_uppercase_and_prefix() {
+        let upper = ABC_SHA256.to_ascii_uppercase();
+        
+        assert_eq!(normalize_hash(&upper).unwrap(), ABC_SHA256);
+        assert_eq!(normalize_hash(&format!(" sha256:{} ", upper)).unwrap(), ABC_SHA256);
+    }
+    
+    #[test]
+    fn normalize_hash_rejects_malformed_hashes() {
+        let too_short = &ABC_SHA256[..SHA256_HEX_LEN - 1];
+        let not_hex = format!("{}g", too_short);
+        let too_long = format!("{}0", ABC_SHA256);
+        
+        for hash in ["", too_short, &not_hex, &too_long, "md5:abc"] {
+            assert!(
+                matches!(normalize_hash(hash), Err(JarvisError::InvalidArgument(_))),
+                "{:?}",
+                hash
+            );
+        }
+    }
+}
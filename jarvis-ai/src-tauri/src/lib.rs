@@ -1,188 +1,3261 @@
-use std::process::{Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::Manager;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt};
 
-// 全局状态管理
+mod bundle;
+mod config;
+mod deps;
+mod diagnostics;
+mod error;
+mod integrity;
+mod lifecycle;
+mod logs;
+mod metrics;
+mod models;
+mod pidfile;
+mod ports;
+mod process;
+mod resources;
+mod settings;
+mod socket;
+mod sse;
+mod uds;
+mod status;
+
+use diagnostics::{DiagnosticCheck, DiagnosticsReport};
+use error::JarvisError;
+use lifecycle::{emit_failure, emit_lifecycle, ErrorInfo, JarvisLifecycle};
+use logs::{LogEntry, LogLevel, LogStream};
+use models::ModelInfo;
+use process::{
+    kill_process_tree, plan_launch, probe_port, send_reload_signal, shutdown_process,
+    spawn_jarvis_process, spawn_supervisor, wait_for_exit, LaunchPlan, PortOccupant,
+    RestartPolicy, ShutdownKind,
+};
+use status::JarvisStatus;
+
+// JARVIS服务默认端口
+const DEFAULT_JARVIS_PORT: u16 = 8000;
+
+// 状态检查请求的默认超时时间及允许的最大值
+const DEFAULT_STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_STATUS_TIMEOUT_MS: u64 = 60_000;
+
+// Python依赖安装的默认总超时时间(秒)
+const DEFAULT_PIP_INSTALL_TIMEOUT_SECS: u64 = 600;
+
+// 强制Python子进程以UTF-8输出，避免Windows控制台(GBK/CP936)下日志中的中文乱码
+const PYTHON_UTF8_ENV: [(&str, &str); 2] = [("PYTHONUTF8", "1"), ("PYTHONIOENCODING", "utf-8")];
+
+// 本地服务默认只监听本机回环地址
+const DEFAULT_BIND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+// Unix socket传输时请求URL使用的地址，实际连接的socket由实例的HTTP客户端决定
+const UNIX_BASE_URL: &str = "http://localhost";
+
+// Python依赖声明文件名
+const REQUIREMENTS_FILE_NAME: &str = "requirements.txt";
+
+// JARVIS核心的模型列表接口路径
+const MODELS_PATH: &str = "/models";
+
+// JARVIS核心的对话接口路径，以及测试对话请求的默认超时时间
+const CHAT_PATH: &str = "/chat";
+const CHAT_TEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+// 以接口方式重新加载时调用的路径，以及重新加载后确认进程仍在运行前的等待时间
+const RELOAD_PATH: &str = "/reload";
+const RELOAD_SETTLE_DELAY: Duration = Duration::from_secs(1);
+
+// JARVIS核心的运行时配置接口路径，支持GET和PUT
+const CORE_CONFIG_PATH: &str = "/config";
+
+// JARVIS核心的流式对话(SSE)接口路径
+const STREAM_PATH: &str = "/stream";
+
+// JARVIS核心的WebSocket接口路径
+const WS_PATH: &str = "/ws";
+
+// 默认的健康检查及就绪检查接口路径
+const DEFAULT_HEALTH_PATH: &str = "/status";
+const DEFAULT_READINESS_PATH: &str = "/";
+
+// HTTP请求的连接超时时间
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// HTTP请求遇到连接错误时的默认重试次数，以及重试间隔的初始值和上限
+const DEFAULT_HTTP_RETRIES: u32 = 2;
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+// 后台状态检查的默认间隔(秒)
+const DEFAULT_STATUS_POLL_INTERVAL_SECS: u64 = 3;
+
+// 超过该时间(秒)没有成功的健康检查即视为服务卡死
+const DEFAULT_STALL_THRESHOLD_SECS: u64 = 30;
+
+// 同时转发给核心的对话、流式输出等请求数的默认上限及允许设置的最大值
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+const MAX_CONCURRENT_REQUESTS_LIMIT: usize = 64;
+
+// 启动后等待服务就绪的默认总超时时间(秒)及最长轮询间隔
+const DEFAULT_READY_TIMEOUT_SECS: u64 = 30;
+const MAX_READY_POLL_DELAY: Duration = Duration::from_secs(2);
+
+// 等待JARVIS进程退出的超时时间
+const PROCESS_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 停止服务时等待进程正常退出的默认宽限期及上限(秒)
+// 关闭应用时也会等待宽限期，核心无响应时宽限期越长退出越慢
+const DEFAULT_SHUTDOWN_GRACE_SECS: u64 = 5;
+const MAX_SHUTDOWN_GRACE_SECS: u64 = 60;
+
+// 强制结束后等待进程退出的最长时间
+const FORCE_KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 运行JARVIS核心所需的最低Python版本
+const MIN_PYTHON_VERSION: (u32, u32) = (3, 9);
+
+// 日志中需要隐藏的环境变量值的最小长度
+const MIN_REDACTED_ENV_LEN: usize = 6;
+
+// 自动启动前等待主窗口创建的最长时间及检查间隔
+const MAIN_WINDOW_TIMEOUT: Duration = Duration::from_secs(10);
+const MAIN_WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// 自动启动失败后的最大尝试次数及重试间隔
+const AUTOSTART_MAX_ATTEMPTS: u32 = 3;
+const AUTOSTART_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+// 核心目录名及其中的入口脚本
+const CORE_DIR_NAME: &str = "jarvis-core";
+const CORE_SCRIPT_NAME: &str = "main.py";
+
+// 默认实例名，未指定实例的命令均作用于该实例
+const DEFAULT_INSTANCE: &str = "default";
+
+// 单个JARVIS后端实例的运行状态
+pub struct ServiceInstance {
+    pub name: String,
+    // 只通过store_process/take_process等方法存取，每次只短暂加锁
+    python_process: Mutex<Option<Child>>,
+    // 串行执行创建和结束进程的操作(可能持续数秒)，进行期间查询PID和运行状态不会被阻塞
+    process_op: Mutex<()>,
+    pub is_jarvis_running: Mutex<bool>,
+    pub port: Mutex<u16>,
+    // 本地服务监听的地址，通过JARVIS_HOST传给核心
+    pub bind_address: Mutex<IpAddr>,
+    pub restart_count: Mutex<u32>,
+    pub supervisor_generation: Mutex<u64>,
+    pub last_exit: Mutex<Option<ExitInfo>>,
+    // 远程模式下的服务地址，为None时使用本地启动的进程
+    pub remote_base_url: Mutex<Option<String>>,
+    // 本地服务监听的Unix socket路径，为None时使用TCP端口
+    pub unix_socket: Mutex<Option<PathBuf>>,
+    // 连接到unix_socket的HTTP客户端，按socket路径缓存
+    unix_client: Mutex<Option<(PathBuf, reqwest::Client)>>,
+    // 最近一次创建进程完成的时间及启动耗时(毫秒)，就绪后用于记录启动耗时
+    spawned: Mutex<Option<(Instant, u64)>>,
+    // 最近一次健康检查成功(200且响应解析成功)的时间，每次启动时清空
+    pub last_successful_health: Mutex<Option<Instant>>,
+    // 最近一次启动(或切换为远程模式)的时间，还没有成功的健康检查时从这里开始计算卡死时长
+    health_window_start: Mutex<Option<Instant>>,
+    // 当前进程是否以独立模式启动，关闭窗口时不停止独立运行的进程
+    pub launched_detached: Mutex<bool>,
+    // 应用启动时重新连接的独立进程PID，没有对应的Child，只能按PID停止
+    pub detached_pid: Mutex<Option<u32>>,
+    // 串行执行启动/停止/重启命令
+    lifecycle: LifecycleQueue,
+}
+
+impl ServiceInstance {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            python_process: Mutex::new(None),
+            process_op: Mutex::new(()),
+            is_jarvis_running: Mutex::new(false),
+            port: Mutex::new(DEFAULT_JARVIS_PORT),
+            bind_address: Mutex::new(DEFAULT_BIND_ADDRESS),
+            restart_count: Mutex::new(0),
+            supervisor_generation: Mutex::new(0),
+            last_exit: Mutex::new(None),
+            remote_base_url: Mutex::new(None),
+            unix_socket: Mutex::new(None),
+            unix_client: Mutex::new(None),
+            spawned: Mutex::new(None),
+            last_successful_health: Mutex::new(None),
+            health_window_start: Mutex::new(None),
+            launched_detached: Mutex::new(false),
+            detached_pid: Mutex::new(None),
+            lifecycle: LifecycleQueue::default(),
+        }
+    }
+    
+    // 是否连接远程服务而不启动本地进程
+    fn is_remote(&self) -> bool {
+        lock(&self.remote_base_url).is_some()
+    }
+    
+    // 实例的服务地址，本地模式下由端口生成；Unix socket传输时为固定地址，经由http_client连接
+    fn base_url(&self) -> String {
+        if let Some(url) = lock(&self.remote_base_url).clone() {
+            return url;
+        }
+        match self.local_socket() {
+            Some(_) => UNIX_BASE_URL.to_string(),
+            None => jarvis_base_url(self.local_addr()),
+        }
+    }
+    
+    // 本地服务使用Unix socket传输时的socket路径，远程模式或TCP传输时为None
+    fn local_socket(&self) -> Option<PathBuf> {
+        if self.is_remote() {
+            return None;
+        }
+        lock(&self.unix_socket).clone()
+    }
+    
+    // 访问本实例所用的HTTP客户端，Unix socket传输时使用连接到该socket的客户端
+    fn http_client(&self, shared: &reqwest::Client) -> reqwest::Client {
+        let Some(path) = self.local_socket() else {
+            return shared.clone();
+        };
+        let mut cached = lock(&self.unix_client);
+        match cached.as_ref() {
+            Some((cached_path, client)) if *cached_path == path => client.clone(),
+            _ => {
+                let client = uds::client(&path);
+                *cached = Some((path, client.clone()));
+                client
+            }
+        }
+    }
+    
+    // 本应用访问本地服务所用的地址，监听所有地址(0.0.0.0或::)时通过回环地址访问
+    fn local_addr(&self) -> SocketAddr {
+        let ip = match *lock(&self.bind_address) {
+            IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            ip => ip,
+        };
+        SocketAddr::new(ip, *lock(&self.port))
+    }
+    
+    // 运行标记，为true时守护线程会在进程意外退出后自动重启
+    fn is_running(&self) -> bool {
+        *lock(&self.is_jarvis_running)
+    }
+    
+    fn set_running(&self, running: bool) {
+        *lock(&self.is_jarvis_running) = running;
+        if running {
+            self.reset_health_window();
+        }
+    }
+    
+    // 重新开始计算健康检查，之前成功的记录作废
+    fn reset_health_window(&self) {
+        *lock(&self.last_successful_health) = None;
+        *lock(&self.health_window_start) = Some(Instant::now());
+    }
+    
+    // 记录一次成功的健康检查
+    fn mark_healthy(&self) {
+        *lock(&self.last_successful_health) = Some(Instant::now());
+    }
+    
+    // 距最近一次成功健康检查的秒数，从未成功时为None
+    fn seconds_since_last_healthy(&self) -> Option<u64> {
+        lock(&self.last_successful_health).map(|at| at.elapsed().as_secs())
+    }
+    
+    // 运行中(或远程模式)超过threshold秒没有成功的健康检查时返回已持续的秒数
+    // 启动后一直没有成功过(端口可连接但从不响应)时从启动时算起
+    fn stalled_seconds(&self, threshold: u64) -> Option<u64> {
+        if !self.is_running() && !self.is_remote() {
+            return None;
+        }
+        let since = lock(&self.last_successful_health).or(*lock(&self.health_window_start))?;
+        let secs = since.elapsed().as_secs();
+        (secs > threshold).then_some(secs)
+    }
+    
+    // 是否持有核心进程(进程可能已退出但尚未被守护线程回收)，包括重新连接的独立进程
+    fn has_process(&self) -> bool {
+        self.has_child() || self.adopted_pid().is_some()
+    }
+    
+    // 重新连接的独立进程PID，进程已退出时清除
+    fn adopted_pid(&self) -> Option<u32> {
+        let mut detached_pid = lock(&self.detached_pid);
+        if !detached_pid.is_some_and(pidfile::is_process_alive) {
+            *detached_pid = None;
+        }
+        *detached_pid
+    }
+    
+    // 进程是否独立于应用运行(本次以独立模式启动或启动时重新连接)
+    fn is_detached(&self) -> bool {
+        *lock(&self.launched_detached) || self.adopted_pid().is_some()
+    }
+    
+    // 是否持有本应用启动的核心进程(不包括重新连接的独立进程)
+    fn has_child(&self) -> bool {
+        lock(&self.python_process).is_some()
+    }
+    
+    // 保存新启动的核心进程
+    fn store_process(&self, child: Child) {
+        *lock(&self.python_process) = Some(child);
+    }
+    
+    // 取出核心进程，之后由调用方负责结束并回收
+    fn take_process(&self) -> Option<Child> {
+        lock(&self.python_process).take()
+    }
+    
+    // 本应用启动的核心进程的PID(不检查进程是否已退出)
+    fn child_id(&self) -> Option<u32> {
+        lock(&self.python_process).as_ref().map(Child::id)
+    }
+    
+    // 检查核心进程是否已退出，已退出时取出进程并返回退出状态
+    fn reap_exited(&self) -> Option<ExitStatus> {
+        let mut process_guard = lock(&self.python_process);
+        let status = process_guard.as_mut()?.try_wait().ok()??;
+        *process_guard = None;
+        Some(status)
+    }
+    
+    // 当前进程PID，进程未运行或已退出时返回None
+    fn pid(&self) -> Option<u32> {
+        let mut process_guard = lock(&self.python_process);
+        let Some(child) = process_guard.as_mut() else {
+            return self.adopted_pid();
+        };
+        match child.try_wait() {
+            Ok(None) => Some(child.id()),
+            _ => None,
+        }
+    }
+}
+
+// 启动/停止/重启命令的目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LifecycleIntent {
+    Start { wait_for_ready: bool },
+    Stop { grace: Duration },
+    Restart,
+}
+
+impl LifecycleIntent {
+    fn describe(self) -> &'static str {
+        match self {
+            LifecycleIntent::Start { .. } => "启动",
+            LifecycleIntent::Stop { .. } => "停止",
+            LifecycleIntent::Restart => "重启",
+        }
+    }
+    
+    fn same_kind(self, other: Self) -> bool {
+        std::mem::discriminant(&self) == std::mem::discriminant(&other)
+    }
+}
+
+// 最近一次实际执行的生命周期命令
+struct AppliedLifecycle {
+    seq: u64,
+    intent: LifecycleIntent,
+    outcome: Result<String, JarvisError>,
+}
+
+// 排队中的最新请求，执行时取走intent
+#[derive(Default)]
+struct DesiredLifecycle {
+    seq: u64,
+    intent: Option<LifecycleIntent>,
+    // 上次执行之后排队的启动请求中是否有要求等待就绪的
+    wait_for_ready: bool,
+}
+
+// 同一实例的生命周期命令队列
 #[derive(Default)]
+struct LifecycleQueue {
+    desired: Mutex<DesiredLifecycle>,
+    applied: tokio::sync::Mutex<Option<AppliedLifecycle>>,
+}
+
+impl LifecycleQueue {
+    // 登记请求后串行执行，apply只对合并后的最终目标调用一次
+    async fn run<F, Fut>(&self, intent: LifecycleIntent, apply: F) -> Result<String, JarvisError>
+    where
+        F: FnOnce(LifecycleIntent) -> Fut,
+        Fut: std::future::Future<Output = Result<String, JarvisError>>,
+    {
+        let seq = {
+            let mut desired = lock(&self.desired);
+            desired.seq += 1;
+            desired.intent = Some(intent);
+            if let LifecycleIntent::Start { wait_for_ready: true } = intent {
+                desired.wait_for_ready = true;
+            }
+            desired.seq
+        };
+        
+        let mut applied = self.applied.lock().await;
+        // 之前的调用方已执行过覆盖本请求的命令时直接返回其结果
+        if applied.as_ref().is_none_or(|applied| applied.seq < seq) {
+            let (target_seq, target) = {
+                let mut desired = lock(&self.desired);
+                let target = match desired.intent.take().unwrap_or(intent) {
+                    // 合并的启动请求中任一调用方要求等待就绪时都等待，结果满足最强的要求
+                    LifecycleIntent::Start { .. } => LifecycleIntent::Start {
+                        wait_for_ready: desired.wait_for_ready,
+                    },
+                    target => target,
+                };
+                desired.wait_for_ready = false;
+                (desired.seq, target)
+            };
+            let outcome = apply(target).await;
+            *applied = Some(AppliedLifecycle {
+                seq: target_seq,
+                intent: target,
+                outcome,
+            });
+        }
+        
+        match applied.as_ref() {
+            Some(applied) if applied.intent.same_kind(intent) => applied.outcome.clone(),
+            Some(applied) => {
+                let result = match &applied.outcome {
+                    Ok(message) => message.clone(),
+                    Err(e) => e.to_string(),
+                };
+                Err(JarvisError::Superseded(format!(
+                    "{}请求已被之后的{}请求取代: {}",
+                    intent.describe(),
+                    applied.intent.describe(),
+                    result
+                )))
+            }
+            None => Err(JarvisError::Internal("生命周期命令未执行".to_string())),
+        }
+    }
+}
+
+// 持有期间表示依赖安装正在进行，离开作用域时清除取消通道
+struct InstallGuard(Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>);
+
+impl InstallGuard {
+    // 登记取消通道，已有安装在进行时返回None
+    fn acquire(
+        slot: &Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
+        cancel: tokio::sync::oneshot::Sender<()>,
+    ) -> Option<Self> {
+        let mut current = lock(slot);
+        if current.as_ref().is_some_and(|sender| !sender.is_closed()) {
+            return None;
+        }
+        *current = Some(cancel);
+        Some(Self(slot.clone()))
+    }
+}
+
+impl Drop for InstallGuard {
+    fn drop(&mut self) {
+        *lock(&self.0) = None;
+    }
+}
+
+// 进程退出方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExitKind {
+    Normal,
+    Error,
+    Signal,
+}
+
+// 进程最近一次退出的信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitInfo {
+    pub kind: ExitKind,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub timestamp_ms: u64,
+}
+
+impl ExitInfo {
+    // 用于展示的退出原因
+    fn describe(&self) -> String {
+        match (self.kind, self.code, self.signal) {
+            (ExitKind::Signal, _, Some(signal)) => format!("进程被信号{}终止", signal),
+            (_, Some(code), _) => format!("进程退出，退出码{}", code),
+            _ => "进程退出".to_string(),
+        }
+    }
+    
+    fn from_status(status: ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+        
+        let kind = if status.success() {
+            ExitKind::Normal
+        } else if signal.is_some() {
+            ExitKind::Signal
+        } else {
+            ExitKind::Error
+        };
+        
+        Self {
+            kind,
+            code: status.code(),
+            signal,
+            timestamp_ms: logs::unix_millis(),
+        }
+    }
+}
+
+// jarvis-exited事件内容
+#[derive(Clone, Serialize)]
+struct ExitedEvent {
+    instance: String,
+    #[serde(flatten)]
+    exit: ExitInfo,
+}
+
+// list_instances返回的实例信息
+#[derive(Serialize)]
+struct InstanceInfo {
+    name: String,
+    port: u16,
+    running: bool,
+    pid: Option<u32>,
+    remote_base_url: Option<String>,
+}
+
+// 后端运行模式：本地启动Python进程，或连接已在其他机器/容器中运行的服务
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BackendMode {
+    Local,
+    Remote,
+}
+
+// 重新加载核心的方式：向进程发送SIGHUP(仅Unix)，或调用核心的/reload接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReloadMechanism {
+    Signal,
+    Endpoint,
+}
+
+impl Default for ReloadMechanism {
+    // Windows没有SIGHUP，默认使用接口
+    fn default() -> Self {
+        if cfg!(unix) {
+            ReloadMechanism::Signal
+        } else {
+            ReloadMechanism::Endpoint
+        }
+    }
+}
+
+// 全局状态管理
 pub struct AppState {
-    pub python_process: Arc<Mutex<Option<std::process::Child>>>,
-    pub is_jarvis_running: Arc<Mutex<bool>>,
+    pub instances: Arc<Mutex<HashMap<String, Arc<ServiceInstance>>>>,
+    pub python_path: Arc<Mutex<Option<String>>>,
+    pub core_script_path: Arc<Mutex<Option<PathBuf>>>,
+    // 最近一次自动查找到的核心脚本，变化时才写入日志
+    pub resolved_core_script: Arc<Mutex<Option<PathBuf>>>,
+    // 核心脚本期望的SHA-256(小写十六进制)，设置后启动前校验，不一致时拒绝启动
+    pub expected_core_hash: Arc<Mutex<Option<String>>>,
+    pub venv_path: Arc<Mutex<Option<PathBuf>>>,
+    pub status_poll_interval_secs: Arc<Mutex<u64>>,
+    pub env_vars: Arc<Mutex<HashMap<String, String>>>,
+    pub extra_args: Arc<Mutex<Vec<String>>>,
+    pub health_path: Arc<Mutex<String>>,
+    pub readiness_path: Arc<Mutex<String>>,
+    pub auto_start: Arc<Mutex<bool>>,
+    // 以独立模式启动核心，应用退出后核心继续运行，下次启动时重新连接
+    pub detached: Arc<Mutex<bool>>,
+    pub log_buffer: Arc<Mutex<VecDeque<LogEntry>>>,
+    pub system_monitor: Arc<Mutex<sysinfo::System>>,
+    // reqwest::Client内部已共享连接池，克隆开销很小
+    pub http_client: reqwest::Client,
+    // 核心要求鉴权时使用的Bearer令牌，只保存在内存中，不写入配置文件、日志和事件
+    pub auth_token: Arc<Mutex<Option<String>>>,
+    pub jarvis_socket: Arc<Mutex<Option<socket::SocketHandle>>>,
+    // 正在进行的依赖安装的取消通道
+    pub pip_cancel: Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
+    pub pip_install_timeout_secs: Arc<Mutex<u64>>,
+    pub shutdown_grace_secs: Arc<Mutex<u64>>,
+    // 实时推送日志的最低级别
+    pub log_level_filter: Arc<Mutex<String>>,
+    // 写入应用日志目录的日志文件，目录不可用时为None
+    pub log_file: Arc<Mutex<Option<logs::RotatingLog>>>,
+    // 等待服务就绪的超时时间，以及应用启动后自动启动服务前的固定延迟(默认0)
+    pub ready_timeout_secs: Arc<Mutex<u64>>,
+    pub startup_delay_ms: Arc<Mutex<u64>>,
+    // 超过该时间没有成功的健康检查时发送jarvis-stalled事件
+    pub stall_threshold_secs: Arc<Mutex<u64>>,
+    // 按实例名缓存的模型列表
+    pub model_cache: Arc<Mutex<HashMap<String, models::ModelCache>>>,
+    // 为true时后台状态检查暂停，不影响核心进程
+    pub monitoring_paused: Arc<Mutex<bool>>,
+    // 状态检查等HTTP请求遇到连接错误时的重试次数
+    pub http_retries: Arc<Mutex<u32>>,
+    pub restart_policy: Arc<Mutex<RestartPolicy>>,
+    pub reload_mechanism: Arc<Mutex<ReloadMechanism>>,
+    // 自定义的核心启动命令模板，未设置时使用Python直接运行核心脚本
+    pub launch_command: Arc<Mutex<Option<Vec<String>>>>,
+    // 正在进行的SSE流，按前端传入的request_id索引
+    pub sse_streams: Arc<Mutex<HashMap<String, tauri::async_runtime::JoinHandle<()>>>>,
+    // 最近一次启动失败或崩溃的信息
+    pub last_error: Arc<Mutex<Option<ErrorInfo>>>,
+    // 最近几次启动的耗时
+    pub start_timings: Arc<Mutex<metrics::TimingHistory>>,
+    // 转发给核心的请求(对话、流式输出、模型列表、核心配置)的并发上限，名额用尽时直接返回TooBusy
+    // 修改上限时替换为新的信号量，进行中的请求仍占用旧信号量的名额直到结束
+    pub max_concurrent_requests: Arc<Mutex<usize>>,
+    pub proxy_permits: Arc<Mutex<Arc<tokio::sync::Semaphore>>>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            instances: Arc::new(Mutex::new(HashMap::from([(
+                DEFAULT_INSTANCE.to_string(),
+                Arc::new(ServiceInstance::new(DEFAULT_INSTANCE)),
+            )]))),
+            python_path: Arc::new(Mutex::new(None)),
+            core_script_path: Arc::new(Mutex::new(None)),
+            resolved_core_script: Arc::new(Mutex::new(None)),
+            expected_core_hash: Arc::new(Mutex::new(None)),
+            venv_path: Arc::new(Mutex::new(None)),
+            status_poll_interval_secs: Arc::new(Mutex::new(DEFAULT_STATUS_POLL_INTERVAL_SECS)),
+            env_vars: Arc::new(Mutex::new(HashMap::new())),
+            extra_args: Arc::new(Mutex::new(Vec::new())),
+            health_path: Arc::new(Mutex::new(DEFAULT_HEALTH_PATH.to_string())),
+            readiness_path: Arc::new(Mutex::new(DEFAULT_READINESS_PATH.to_string())),
+            auto_start: Arc::new(Mutex::new(true)),
+            detached: Arc::new(Mutex::new(false)),
+            log_buffer: Arc::new(Mutex::new(VecDeque::with_capacity(logs::LOG_BUFFER_CAPACITY))),
+            system_monitor: Arc::new(Mutex::new(sysinfo::System::new())),
+            http_client: build_http_client(),
+            auth_token: Arc::new(Mutex::new(None)),
+            jarvis_socket: Arc::new(Mutex::new(None)),
+            pip_cancel: Arc::new(Mutex::new(None)),
+            pip_install_timeout_secs: Arc::new(Mutex::new(DEFAULT_PIP_INSTALL_TIMEOUT_SECS)),
+            shutdown_grace_secs: Arc::new(Mutex::new(DEFAULT_SHUTDOWN_GRACE_SECS)),
+            log_level_filter: Arc::new(Mutex::new(logs::DEFAULT_LOG_LEVEL.to_string())),
+            log_file: Arc::new(Mutex::new(None)),
+            ready_timeout_secs: Arc::new(Mutex::new(DEFAULT_READY_TIMEOUT_SECS)),
+            startup_delay_ms: Arc::new(Mutex::new(0)),
+            stall_threshold_secs: Arc::new(Mutex::new(DEFAULT_STALL_THRESHOLD_SECS)),
+            model_cache: Arc::new(Mutex::new(HashMap::new())),
+            monitoring_paused: Arc::new(Mutex::new(false)),
+            http_retries: Arc::new(Mutex::new(DEFAULT_HTTP_RETRIES)),
+            restart_policy: Arc::new(Mutex::new(RestartPolicy::default())),
+            reload_mechanism: Arc::new(Mutex::new(ReloadMechanism::default())),
+            launch_command: Arc::new(Mutex::new(None)),
+            sse_streams: Arc::new(Mutex::new(HashMap::new())),
+            last_error: Arc::new(Mutex::new(None)),
+            start_timings: Arc::new(Mutex::new(metrics::TimingHistory::default())),
+            max_concurrent_requests: Arc::new(Mutex::new(DEFAULT_MAX_CONCURRENT_REQUESTS)),
+            proxy_permits: Arc::new(Mutex::new(Arc::new(tokio::sync::Semaphore::new(
+                DEFAULT_MAX_CONCURRENT_REQUESTS,
+            )))),
+        }
+    }
+}
+
+impl AppState {
+    // 查找已注册的实例
+    pub fn find_instance(&self, name: &str) -> Result<Arc<ServiceInstance>, JarvisError> {
+        lock(&self.instances)
+            .get(name)
+            .cloned()
+            .ok_or_else(|| JarvisError::InstanceNotFound(format!("JARVIS实例不存在: {}", name)))
+    }
+    
+    // 获取实例，不存在时注册一个新实例
+    // 只用于启动、配置实例(端口、传输方式等)和诊断，新实例可以先设置端口再启动；
+    // 查询和停止等命令使用find_instance，避免实例名拼写错误时产生多余的实例
+    pub fn instance(&self, name: &str) -> Arc<ServiceInstance> {
+        lock(&self.instances)
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(ServiceInstance::new(name)))
+            .clone()
+    }
+    
+    // 获取一个转发请求的名额，已达到并发上限时返回TooBusy而不排队
+    fn acquire_proxy_permit(&self) -> Result<tokio::sync::OwnedSemaphorePermit, JarvisError> {
+        let permits = lock(&self.proxy_permits).clone();
+        permits.try_acquire_owned().map_err(|_| {
+            JarvisError::TooBusy(format!(
+                "同时进行的请求已达上限({})，请稍后再试",
+                *lock(&self.max_concurrent_requests)
+            ))
+        })
+    }
+    
+    // 设置并发上限
+    fn set_max_concurrent_requests(&self, permits: usize) {
+        *lock(&self.max_concurrent_requests) = permits;
+        *lock(&self.proxy_permits) = Arc::new(tokio::sync::Semaphore::new(permits));
+    }
+    
+    // 附带当前访问令牌的JARVIS服务客户端
+    fn jarvis_http(&self, instance: &ServiceInstance) -> JarvisHttp {
+        JarvisHttp {
+            client: instance.http_client(&self.http_client),
+            auth_token: lock(&self.auth_token).clone(),
+        }
+    }
+    
+    // 写入日志文件和日志缓冲区，写文件失败时在缓冲区中记录原因
+    fn record_log(&self, entry: LogEntry) {
+        let write_error = lock(&self.log_file)
+            .as_mut()
+            .and_then(|log_file| log_file.write(&entry).err());
+        
+        let mut buffer = lock(&self.log_buffer);
+        if let Some(e) = write_error {
+            let message = format!("写入日志文件失败: {}", e);
+            logs::push_log(&mut buffer, LogEntry::new(&entry.instance, LogStream::App, message));
+        }
+        logs::push_log(&mut buffer, entry);
+    }
+    
+    // 记录应用自身的日志，与核心输出一起保存，可通过get_logs查看
+    pub fn log_app(&self, instance: &str, message: impl Into<String>) {
+        self.record_log(LogEntry::new(instance, LogStream::App, message.into()));
+    }
+    
+    // 默认实例
+    pub fn default_instance(&self) -> Arc<ServiceInstance> {
+        self.instance(DEFAULT_INSTANCE)
+    }
+    
+    // 所有已注册的实例
+    pub fn all_instances(&self) -> Vec<Arc<ServiceInstance>> {
+        lock(&self.instances).values().cloned().collect()
+    }
+    
+    // 停止服务时等待进程正常退出的宽限期
+    pub fn shutdown_grace(&self) -> Duration {
+        Duration::from_secs(*lock(&self.shutdown_grace_secs))
+    }
+    
+    // 启动后等待服务就绪的总超时时间
+    pub fn ready_timeout(&self) -> Duration {
+        Duration::from_secs(*lock(&self.ready_timeout_secs))
+    }
+}
+
+// 访问JARVIS服务所用的HTTP客户端，设置了访问令牌时为每个请求附加Authorization头
+// 仅用于JARVIS服务地址，探测任意URL时直接使用http_client，避免令牌泄露给第三方
+#[derive(Clone)]
+struct JarvisHttp {
+    client: reqwest::Client,
+    auth_token: Option<String>,
+}
+
+impl JarvisHttp {
+    fn request<U: reqwest::IntoUrl>(
+        &self,
+        method: reqwest::Method,
+        url: U,
+    ) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.auth_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+    
+    fn get<U: reqwest::IntoUrl>(&self, url: U) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::GET, url)
+    }
+    
+    fn post<U: reqwest::IntoUrl>(&self, url: U) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::POST, url)
+    }
+    
+    fn put<U: reqwest::IntoUrl>(&self, url: U) -> reqwest::RequestBuilder {
+        self.request(reqwest::Method::PUT, url)
+    }
+}
+
+// 401/403说明核心要求鉴权，单独返回Unauthorized以便前端提示输入访问令牌
+fn check_auth(status: reqwest::StatusCode) -> Result<(), JarvisError> {
+    if matches!(status, reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) {
+        return Err(JarvisError::Unauthorized(format!(
+            "JARVIS服务拒绝访问({})，请设置正确的访问令牌",
+            status
+        )));
+    }
+    Ok(())
+}
+
+// 创建所有请求共用的HTTP客户端，复用连接以减少频繁轮询的开销
+// JARVIS服务只在本机监听，不走系统代理
+fn build_http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .no_proxy()
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+// 解析命令传入的实例名，未指定时使用默认实例
+fn instance_name(instance: Option<String>) -> Result<String, JarvisError> {
+    let name = instance
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| DEFAULT_INSTANCE.to_string());
+    
+    // 实例名会用于PID文件名，只允许字母、数字、-和_
+    if name.len() > 32
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(JarvisError::InvalidArgument(format!("无效的实例名: {}", name)));
+    }
+    Ok(name)
+}
+
+// 获取锁，持有锁的线程panic导致锁中毒时仍恢复内部数据，避免后续命令连锁panic
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// 校验端口范围(1024-65535)
+fn validate_port(port: u16) -> Result<(), JarvisError> {
+    if port < 1024 {
+        return Err(JarvisError::InvalidArgument(format!("端口必须在1024-65535之间: {}", port)));
+    }
+    Ok(())
+}
+
+// 根据监听地址构建JARVIS服务地址
+fn jarvis_base_url(addr: SocketAddr) -> String {
+    format!("http://{}", addr)
+}
+
+// 校验远程服务地址，并去掉末尾的/以便拼接接口路径
+fn normalize_remote_url(url: &str) -> Result<String, JarvisError> {
+    parse_http_url(url.trim())?;
+    Ok(url.trim().trim_end_matches('/').to_string())
+}
+
+// 根据服务地址和接口路径构建请求地址
+fn jarvis_url(base_url: &str, path: &str) -> String {
+    format!("{}{}", base_url, path)
+}
+
+// 解析URL，只允许http和https
+fn parse_http_url(url: &str) -> Result<reqwest::Url, JarvisError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| JarvisError::InvalidArgument(format!("无效的URL {}: {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(JarvisError::InvalidArgument(format!(
+            "只支持http和https地址: {}",
+            url
+        )));
+    }
+    Ok(parsed)
+}
+
+// 校验接口路径：必须以/开头，且不含空白或控制字符
+fn validate_http_path(path: &str) -> Result<(), JarvisError> {
+    if !path.starts_with('/') || path.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(JarvisError::InvalidArgument(format!("无效的接口路径: {:?}", path)));
+    }
+    Ok(())
+}
+
+// JARVIS状态检查命令
+// timeout_ms仅限制本次HTTP请求的耗时(默认5秒，最长60秒)，不包含服务启动所需时间
+#[tauri::command]
+async fn check_jarvis_status(
+    app_state: tauri::State<'_, AppState>,
+    timeout_ms: Option<u64>,
+    instance: Option<String>,
+) -> Result<JarvisStatus, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let timeout = match timeout_ms {
+        Some(0) => return Err(JarvisError::InvalidArgument("超时时间必须大于0".to_string())),
+        Some(ms) => Duration::from_millis(ms.min(MAX_STATUS_TIMEOUT_MS)),
+        None => DEFAULT_STATUS_TIMEOUT,
+    };
+    
+    let base_url = instance.base_url();
+    let health_path = lock(&app_state.health_path).clone();
+    let retries = *lock(&app_state.http_retries);
+    let client = app_state.jarvis_http(&instance);
+    let status = fetch_jarvis_status(&client, &base_url, &health_path, timeout, retries).await?;
+    instance.mark_healthy();
+    Ok(status)
+}
+
+// 距实例最近一次成功健康检查的秒数，从未成功时为None
+#[tauri::command]
+async fn seconds_since_last_healthy(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<Option<u64>, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    Ok(instance.seconds_since_last_healthy())
+}
+
+// 发起GET请求，连接失败(如服务刚启动尚未监听端口)时按指数退避重试
+// timeout限制每次请求的耗时，HTTP错误状态码不重试
+async fn get_with_retry(
+    client: &JarvisHttp,
+    url: &str,
+    timeout: Duration,
+    retries: u32,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let mut backoff = INITIAL_RETRY_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        match client.get(url).timeout(timeout).send().await {
+            Err(e) if e.is_connect() && attempt < retries => {
+                attempt += 1;
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+            }
+            result => return result,
+        }
+    }
+}
+
+// 请求JARVIS服务的接口，返回状态码和响应内容
+async fn get_jarvis(
+    client: &JarvisHttp,
+    base_url: &str,
+    path: &str,
+    timeout: Duration,
+    retries: u32,
+) -> Result<(reqwest::StatusCode, Vec<u8>), JarvisError> {
+    let response = get_with_retry(client, &jarvis_url(base_url, path), timeout, retries)
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                JarvisError::Timeout(format!("连接JARVIS服务超时: {}", e))
+            } else {
+                JarvisError::HttpError(format!("连接JARVIS服务失败: {}", e))
+            }
+        })?;
+    let status = response.status();
+    check_auth(status)?;
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| JarvisError::HttpError(format!("读取JARVIS服务响应失败: {}", e)))?;
+    Ok((status, body.to_vec()))
+}
+
+// 请求JARVIS服务的健康检查接口(默认/status)，retries为连接失败时的重试次数
+async fn fetch_jarvis_status(
+    client: &JarvisHttp,
+    base_url: &str,
+    health_path: &str,
+    timeout: Duration,
+    retries: u32,
+) -> Result<JarvisStatus, JarvisError> {
+    let (status, body) = get_jarvis(client, base_url, health_path, timeout, retries).await?;
+    if !status.is_success() {
+        return Err(JarvisError::HttpError(format!("JARVIS服务响应错误: {}", status)));
+    }
+    
+    let json = serde_json::from_slice::<Value>(&body)
+        .map_err(|e| JarvisError::HttpError(format!("解析响应失败: {}", e)))?;
+    Ok(JarvisStatus::from_value(json))
+}
+
+// probe_url的探测结果
+#[derive(Serialize)]
+struct ProbeResult {
+    status: u16,
+    latency_ms: u64,
+    is_json: bool,
+}
+
+// 对任意http/https地址发起一次GET请求，用于排查反向代理等网络问题
+#[tauri::command]
+async fn probe_url(
+    app_state: tauri::State<'_, AppState>,
+    url: String,
+    timeout_ms: u64,
+) -> Result<ProbeResult, JarvisError> {
+    let parsed = parse_http_url(&url)?;
+    if timeout_ms == 0 {
+        return Err(JarvisError::InvalidArgument("超时时间必须大于0".to_string()));
+    }
+    let timeout = Duration::from_millis(timeout_ms.min(MAX_STATUS_TIMEOUT_MS));
+    
+    let started = Instant::now();
+    let response = app_state
+        .http_client
+        .get(parsed)
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                JarvisError::Timeout(format!("请求{}超时: {}", url, e))
+            } else {
+                JarvisError::HttpError(format!("请求{}失败: {}", url, e))
+            }
+        })?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| JarvisError::HttpError(format!("读取{}的响应失败: {}", url, e)))?;
+    
+    Ok(ProbeResult {
+        status,
+        latency_ms,
+        is_json: serde_json::from_slice::<Value>(&body).is_ok(),
+    })
+}
+
+// 获取JARVIS核心支持的模型列表，结果会缓存一小段时间，force为true时忽略缓存
+#[tauri::command]
+async fn list_models(
+    app_state: tauri::State<'_, AppState>,
+    force: Option<bool>,
+    instance: Option<String>,
+) -> Result<Vec<ModelInfo>, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let base_url = instance.base_url();
+    if !force.unwrap_or(false) {
+        let cached = lock(&app_state.model_cache)
+            .get(&instance.name)
+            .and_then(|cache| cache.get(&base_url));
+        if let Some(models) = cached {
+            return Ok(models);
+        }
+    }
+    
+    let _permit = app_state.acquire_proxy_permit()?;
+    let retries = *lock(&app_state.http_retries);
+    let client = &app_state.jarvis_http(&instance);
+    let (status, body) =
+        get_jarvis(client, &base_url, MODELS_PATH, DEFAULT_STATUS_TIMEOUT, retries).await?;
+    
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(JarvisError::NotSupported(
+            "当前JARVIS核心版本不支持获取模型列表(/models接口不存在)".to_string(),
+        ));
+    }
+    if !status.is_success() {
+        return Err(JarvisError::HttpError(format!("获取模型列表失败: {}", status)));
+    }
+    
+    let json = serde_json::from_slice::<Value>(&body)
+        .map_err(|e| JarvisError::HttpError(format!("解析模型列表响应失败: {}", e)))?;
+    let models = models::parse_models(json).map_err(JarvisError::HttpError)?;
+    
+    lock(&app_state.model_cache).insert(
+        instance.name.clone(),
+        models::ModelCache::new(base_url, models.clone()),
+    );
+    Ok(models)
+}
+
+// /chat接口的返回内容
+#[derive(Deserialize)]
+struct ChatReply {
+    response: String,
+    #[serde(default, alias = "model_used")]
+    model: Option<String>,
+}
+
+// test_chat_request的测试结果
+#[derive(Serialize)]
+struct ChatTestResult {
+    latency_ms: u64,
+    response_text: String,
+    model: Option<String>,
+}
+
+// 向JARVIS核心发送一条测试对话并统计完整往返耗时，用于端到端连接测试
+// 超时、非200状态码和响应解析失败分别返回timeout、http_error和invalid_response错误
+#[tauri::command]
+async fn test_chat_request(
+    app_state: tauri::State<'_, AppState>,
+    prompt: String,
+    timeout_ms: Option<u64>,
+    instance: Option<String>,
+) -> Result<ChatTestResult, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let timeout = match timeout_ms {
+        Some(0) => return Err(JarvisError::InvalidArgument("超时时间必须大于0".to_string())),
+        Some(ms) => Duration::from_millis(ms.min(MAX_STATUS_TIMEOUT_MS)),
+        None => CHAT_TEST_TIMEOUT,
+    };
+    let _permit = app_state.acquire_proxy_permit()?;
+    
+    let started = Instant::now();
+    let response = app_state
+        .jarvis_http(&instance)
+        .post(jarvis_url(&instance.base_url(), CHAT_PATH))
+        .json(&serde_json::json!({ "message": prompt }))
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                JarvisError::Timeout(format!("测试对话请求超时({}毫秒)", timeout.as_millis()))
+            } else {
+                JarvisError::HttpError(format!("发送测试对话请求失败: {}", e))
+            }
+        })?;
+    
+    let status = response.status();
+    check_auth(status)?;
+    let body = response.bytes().await.map_err(|e| {
+        if e.is_timeout() {
+            JarvisError::Timeout(format!("读取测试对话响应超时({}毫秒)", timeout.as_millis()))
+        } else {
+            JarvisError::HttpError(format!("读取测试对话响应失败: {}", e))
+        }
+    })?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    
+    if !status.is_success() {
+        return Err(JarvisError::HttpError(format!(
+            "测试对话请求失败: {} {}",
+            status,
+            String::from_utf8_lossy(&body).trim()
+        )));
+    }
+    
+    let reply: ChatReply = serde_json::from_slice(&body)
+        .map_err(|e| JarvisError::InvalidResponse(format!("解析测试对话响应失败: {}", e)))?;
+    Ok(ChatTestResult {
+        latency_ms,
+        response_text: reply.response,
+        model: reply.model,
+    })
+}
+
+// 获取JARVIS核心的运行时配置(如temperature、system prompt)
+#[tauri::command]
+async fn get_core_config(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<Value, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let _permit = app_state.acquire_proxy_permit()?;
+    let retries = *lock(&app_state.http_retries);
+    fetch_core_config(&app_state.jarvis_http(&instance), &instance.base_url(), retries).await
+}
+
+// 部分更新JARVIS核心的运行时配置：先读取当前配置，合并patch后整体写回
+// patch中值为null的字段会被删除，核心返回的校验错误原样返回给前端
+#[tauri::command]
+async fn update_core_config(
+    app_state: tauri::State<'_, AppState>,
+    patch: Value,
+    instance: Option<String>,
+) -> Result<(), JarvisError> {
+    if !patch.is_object() {
+        return Err(JarvisError::InvalidArgument("配置更新内容必须是JSON对象".to_string()));
+    }
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let _permit = app_state.acquire_proxy_permit()?;
+    let base_url = instance.base_url();
+    let retries = *lock(&app_state.http_retries);
+    
+    let http = app_state.jarvis_http(&instance);
+    let mut config = fetch_core_config(&http, &base_url, retries).await?;
+    merge_json(&mut config, patch);
+    
+    let response = http
+        .put(jarvis_url(&base_url, CORE_CONFIG_PATH))
+        .json(&config)
+        .timeout(DEFAULT_STATUS_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                JarvisError::Timeout(format!("更新核心配置超时: {}", e))
+            } else {
+                JarvisError::HttpError(format!("更新核心配置失败: {}", e))
+            }
+        })?;
+    
+    let status = response.status();
+    check_auth(status)?;
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    if status.is_client_error() {
+        Err(JarvisError::InvalidArgument(body))
+    } else {
+        Err(JarvisError::HttpError(format!("更新核心配置失败: {} {}", status, body.trim())))
+    }
+}
+
+// 读取核心的/config接口，核心版本不支持时返回NotSupported
+async fn fetch_core_config(
+    client: &JarvisHttp,
+    base_url: &str,
+    retries: u32,
+) -> Result<Value, JarvisError> {
+    let (status, body) =
+        get_jarvis(client, base_url, CORE_CONFIG_PATH, DEFAULT_STATUS_TIMEOUT, retries).await?;
+    if matches!(
+        status,
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::METHOD_NOT_ALLOWED
+    ) {
+        return Err(JarvisError::NotSupported(
+            "当前JARVIS核心版本不支持运行时配置(/config接口不存在)".to_string(),
+        ));
+    }
+    if !status.is_success() {
+        return Err(JarvisError::HttpError(format!(
+            "获取核心配置失败: {} {}",
+            status,
+            String::from_utf8_lossy(&body).trim()
+        )));
+    }
+    
+    let config: Value = serde_json::from_slice(&body)
+        .map_err(|e| JarvisError::InvalidResponse(format!("解析核心配置失败: {}", e)))?;
+    if !config.is_object() {
+        return Err(JarvisError::InvalidResponse("核心配置不是JSON对象".to_string()));
+    }
+    Ok(config)
+}
+
+// 按JSON Merge Patch的规则合并：对象逐字段递归合并，null表示删除字段，其他值直接替换
+fn merge_json(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+    
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            merge_json(target.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+// 向JARVIS核心的/stream接口发起流式对话，输出通过jarvis-sse-chunk事件逐段推送
+// 结束时发送jarvis-sse-done，连接失败或中途断开时发送jarvis-sse-error
+#[tauri::command]
+async fn start_sse_stream(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    prompt: String,
+    request_id: String,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    if request_id.trim().is_empty() {
+        return Err(JarvisError::InvalidArgument("request_id不能为空".to_string()));
+    }
+    
+    let mut streams = lock(&app_state.sse_streams);
+    if streams.contains_key(&request_id) {
+        return Err(JarvisError::AlreadyRunning(format!("请求{}正在进行", request_id)));
+    }
+    let permit = app_state.acquire_proxy_permit()?;
+    
+    let request = app_state
+        .jarvis_http(&instance)
+        .post(jarvis_url(&instance.base_url(), STREAM_PATH))
+        .json(&serde_json::json!({ "message": prompt }));
+    let task = sse::start(app, request, request_id.clone(), permit);
+    streams.insert(request_id.clone(), task);
+    Ok(request_id)
+}
+
+// 取消正在进行的SSE流
+#[tauri::command]
+async fn cancel_sse_stream(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    request_id: String,
+) -> Result<String, JarvisError> {
+    let Some(task) = lock(&app_state.sse_streams).remove(&request_id) else {
+        return Err(JarvisError::NotRunning(format!("没有正在进行的请求{}", request_id)));
+    };
+    sse::cancel(&app, &request_id, task);
+    Ok(format!("已取消请求{}", request_id))
+}
+
+// 打开到JARVIS核心/ws接口的WebSocket连接，已有连接时先关闭旧连接
+// 断开后自动重连，连接状态通过jarvis-ws-state事件通知前端
+#[tauri::command]
+async fn open_jarvis_socket(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let base_url = instance.base_url();
+    
+    // http://host:port -> ws://host:port，https对应wss
+    let url = match base_url.strip_prefix("http") {
+        Some(rest) => format!("ws{}{}", rest, WS_PATH),
+        None => format!("{}{}", base_url, WS_PATH),
+    };
+    
+    let auth_token = lock(&app_state.auth_token).clone();
+    let handle = socket::open(app.clone(), url.clone(), auth_token, instance.local_socket());
+    if let Some(old) = lock(&app_state.jarvis_socket).replace(handle) {
+        old.close(&app);
+    }
+    Ok(format!("正在连接{}", url))
+}
+
+// 通过已打开的WebSocket连接发送消息
+#[tauri::command]
+async fn send_jarvis_message(app_state: tauri::State<'_, AppState>, text: String) -> Result<(), JarvisError> {
+    match lock(&app_state.jarvis_socket).as_ref() {
+        Some(handle) if handle.send(text) => Ok(()),
+        _ => Err(JarvisError::NotRunning("WebSocket连接未打开".to_string())),
+    }
+}
+
+// 关闭WebSocket连接
+#[tauri::command]
+async fn close_jarvis_socket(app: AppHandle, app_state: tauri::State<'_, AppState>) -> Result<(), JarvisError> {
+    if let Some(handle) = lock(&app_state.jarvis_socket).take() {
+        handle.close(&app);
+    }
+    Ok(())
+}
+
+// jarvis-status-changed事件内容
+#[derive(Clone, PartialEq, Serialize)]
+struct StatusChangedEvent {
+    instance: String,
+    reachable: bool,
+    status: Option<JarvisStatus>,
+}
+
+// jarvis-health-up/jarvis-health-down事件内容，previous_state_ms为切换前状态持续的时长，
+// 首次检查成功时没有之前的状态，为None
+#[derive(Clone, Serialize)]
+struct HealthEdgeEvent {
+    instance: String,
+    previous_state_ms: Option<u64>,
+}
+
+// 实例最近一次的可达状态及其开始时间
+struct HealthState {
+    reachable: bool,
+    since: Instant,
+}
+
+// 可达状态切换时发送边沿事件；首次检查成功之前的失败不视为下线
+fn track_health(
+    app: &AppHandle,
+    health: &mut HashMap<String, HealthState>,
+    name: &str,
+    reachable: bool,
+) {
+    let previous = health.get(name).map(|state| (state.reachable, state.since));
+    let previous_state_ms = match previous {
+        Some((was_reachable, _)) if was_reachable == reachable => return,
+        None if !reachable => return,
+        Some((_, since)) => Some(since.elapsed().as_millis() as u64),
+        None => None,
+    };
+    
+    let event_name = if reachable { "jarvis-health-up" } else { "jarvis-health-down" };
+    let event = HealthEdgeEvent {
+        instance: name.to_string(),
+        previous_state_ms,
+    };
+    let _ = app.emit(event_name, event);
+    health.insert(
+        name.to_string(),
+        HealthState {
+            reachable,
+            since: Instant::now(),
+        },
+    );
+}
+
+// jarvis-stalled事件内容
+#[derive(Clone, Serialize)]
+struct StalledEvent {
+    instance: String,
+    seconds_since_last_healthy: u64,
+}
+
+// jarvis-resources事件内容
+#[derive(Clone, Serialize)]
+struct ResourcesEvent {
+    instance: String,
+    #[serde(flatten)]
+    stats: resources::ResourceStats,
+}
+
+// 后台定时检查各实例的状态，仅在状态变化时通知前端，同时推送运行中进程的资源占用
+fn spawn_status_poller(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_events: HashMap<String, StatusChangedEvent> = HashMap::new();
+        let mut health: HashMap<String, HealthState> = HashMap::new();
+        let mut stalled: HashSet<String> = HashSet::new();
+        
+        loop {
+            let interval = *lock(&app.state::<AppState>().status_poll_interval_secs);
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+            
+            // 暂停期间跳过检查，恢复后重新推送一次完整状态
+            if *lock(&app.state::<AppState>().monitoring_paused) {
+                last_events.clear();
+                continue;
+            }
+            
+            let instances = app.state::<AppState>().all_instances();
+            let health_path = lock(&app.state::<AppState>().health_path).clone();
+            let stall_threshold = *lock(&app.state::<AppState>().stall_threshold_secs);
+            
+            for instance in &instances {
+                let Some(pid) = instance.pid() else {
+                    continue;
+                };
+                let stats = {
+                    let app_state = app.state::<AppState>();
+                    let mut system = lock(&app_state.system_monitor);
+                    resources::sample(&mut system, pid)
+                };
+                if let Some(stats) = stats {
+                    let event = ResourcesEvent {
+                        instance: instance.name.clone(),
+                        stats,
+                    };
+                    let _ = app.emit("jarvis-resources", event);
+                }
+            }
+            
+            for instance in &instances {
+                let name = instance.name.clone();
+                let base_url = instance.base_url();
+                let client = app.state::<AppState>().jarvis_http(instance);
+                // 后台检查周期性执行，失败时不重试
+                let status =
+                    fetch_jarvis_status(&client, &base_url, &health_path, DEFAULT_STATUS_TIMEOUT, 0)
+                        .await
+                        .ok();
+                if status.is_some() {
+                    instance.mark_healthy();
+                }
+                
+                // 端口仍可连接但长时间没有成功响应时发送jarvis-stalled，跨过阈值时通知一次
+                match instance.stalled_seconds(stall_threshold) {
+                    None => {
+                        stalled.remove(&name);
+                    }
+                    Some(secs) if stalled.insert(name.clone()) => {
+                        let event = StalledEvent {
+                            instance: name.clone(),
+                            seconds_since_last_healthy: secs,
+                        };
+                        let _ = app.emit("jarvis-stalled", event);
+                    }
+                    Some(_) => {}
+                }
+                
+                let event = StatusChangedEvent {
+                    instance: name.clone(),
+                    reachable: status.is_some(),
+                    status,
+                };
+                track_health(&app, &mut health, &name, event.reachable);
+                
+                if last_events.get(&name) != Some(&event) {
+                    let _ = app.emit("jarvis-status-changed", &event);
+                    last_events.insert(name, event);
+                }
+            }
+        }
+    });
+}
+
+// 隐藏输出中出现的自定义环境变量值(如API密钥)，过短的值不做处理以免误伤正常日志
+fn redact_env_values(app: &AppHandle, line: &str) -> String {
+    let app_state = app.state::<AppState>();
+    let env_vars = lock(&app_state.env_vars);
+    let auth_token = lock(&app_state.auth_token);
+    
+    env_vars
+        .values()
+        .chain(auth_token.iter())
+        .filter(|value| value.len() >= MIN_REDACTED_ENV_LEN)
+        .fold(line.to_string(), |line, value| line.replace(value.as_str(), "******"))
+}
+
+// 将子进程输出逐行转发为前端事件并写入日志缓冲区，管道关闭(进程退出)时线程结束
+fn forward_output<R: Read + Send + 'static>(
+    app: AppHandle,
+    reader: R,
+    stream: LogStream,
+    instance: String,
+) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    let line = redact_env_values(&app, line.trim_end_matches(['\r', '\n']));
+                    
+                    // 实时事件按级别过滤，日志缓冲区保留全部内容
+                    let app_state = app.state::<AppState>();
+                    let min_level = LogLevel::parse(&lock(&app_state.log_level_filter))
+                        .unwrap_or(LogLevel::Warning);
+                    if logs::passes_filter(&line, min_level) {
+                        let _ = app.emit(stream.event_name(), &line);
+                    }
+                    app_state.record_log(LogEntry::new(&instance, stream, line));
+                }
+            }
+        }
+    });
+}
+
+// 项目根目录(当前目录的上级目录)
+fn project_root() -> Result<PathBuf, JarvisError> {
+    let dir = std::env::current_dir()
+        .map_err(|e| JarvisError::Io(format!("获取当前目录失败: {}", e)))?;
+    parent_dir(&dir).ok_or_else(|| JarvisError::Io(format!("无法找到{}的父目录", dir.display())))
+}
+
+// 路径的上级目录，根目录或不带目录的相对路径(上级为空路径)返回None
+fn parent_dir(path: &Path) -> Option<PathBuf> {
+    path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+}
+
+// 查找requirements.txt，当前目录在安装包启动等场景下不可靠，因此优先使用核心脚本所在的项目目录
+// 依次查找核心脚本的工作目录、资源目录、可执行文件所在目录和当前目录的上级目录
+fn resolve_requirements(app: &AppHandle) -> Result<(PathBuf, PathBuf), JarvisError> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Ok((_, working_dir)) = resolve_core_script(app) {
+        candidates.push(working_dir);
+    }
+    if let Ok(dir) = app.path().resource_dir() {
+        candidates.push(dir);
+    }
+    if let Some(dir) = std::env::current_exe().ok().and_then(|exe| parent_dir(&exe)) {
+        candidates.push(dir);
+    }
+    if let Ok(root) = project_root() {
+        candidates.push(root);
+    }
+    find_requirements(&candidates)
+}
+
+// 在候选目录中查找requirements.txt，返回文件路径及所在目录
+fn find_requirements(candidates: &[PathBuf]) -> Result<(PathBuf, PathBuf), JarvisError> {
+    if let Some(dir) = candidates.iter().find(|dir| dir.join(REQUIREMENTS_FILE_NAME).is_file()) {
+        return Ok((dir.join(REQUIREMENTS_FILE_NAME), dir.clone()));
+    }
+    
+    let tried: Vec<String> = candidates
+        .iter()
+        .map(|dir| dir.join(REQUIREMENTS_FILE_NAME).display().to_string())
+        .collect();
+    Err(JarvisError::RequirementsMissing(format!(
+        "requirements.txt文件不存在，已尝试: {}",
+        tried.join(", ")
+    )))
+}
+
+// 解析用户指定的依赖文件，相对于项目目录(核心脚本的工作目录，无法确定时为当前目录的上级目录)，
+// 不允许绝对路径或通过..及符号链接指向项目目录之外
+fn resolve_requirements_file(app: &AppHandle, file: &str) -> Result<(PathBuf, PathBuf), JarvisError> {
+    let relative = Path::new(file.trim());
+    let escapes = relative
+        .components()
+        .any(|component| !matches!(component, std::path::Component::Normal(_)));
+    if relative.as_os_str().is_empty() || escapes {
+        return Err(JarvisError::InvalidArgument(format!(
+            "依赖文件{}必须是项目目录内的相对路径",
+            file
+        )));
+    }
+    
+    let root = match resolve_core_script(app) {
+        Ok((_, working_dir)) => working_dir,
+        Err(_) => project_root()?,
+    };
+    let path = root.join(relative);
+    let absolute = std::path::absolute(&path).unwrap_or_else(|_| path.clone());
+    if !path.is_file() {
+        return Err(JarvisError::RequirementsMissing(format!(
+            "依赖文件不存在: {}",
+            absolute.display()
+        )));
+    }
+    
+    let canonical_root = root
+        .canonicalize()
+        .map_err(|e| JarvisError::Io(format!("解析项目目录{}失败: {}", root.display(), e)))?;
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| JarvisError::Io(format!("解析依赖文件{}失败: {}", absolute.display(), e)))?;
+    if !canonical.starts_with(&canonical_root) {
+        return Err(JarvisError::InvalidArgument(format!(
+            "依赖文件{}位于项目目录之外",
+            canonical.display()
+        )));
+    }
+    Ok((canonical, root))
+}
+
+// 解析基础Python可执行文件，未配置时使用系统默认命令
+fn base_python_executable(app_state: &AppState) -> String {
+    lock(&app_state.python_path).clone().unwrap_or_else(|| {
+        if cfg!(windows) {
+            "python".to_string()
+        } else {
+            "python3".to_string()
+        }
+    })
+}
+
+// 虚拟环境中的Python解释器路径
+fn venv_python(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts").join("python.exe")
+    } else {
+        venv.join("bin").join("python")
+    }
+}
+
+// 解析实际使用的Python解释器，配置了虚拟环境时直接调用其中的解释器
+fn resolve_python_executable(app_state: &AppState) -> Result<String, JarvisError> {
+    let Some(venv) = lock(&app_state.venv_path).clone() else {
+        return Ok(base_python_executable(app_state));
+    };
+    
+    let python = venv_python(&venv);
+    if !python.is_file() {
+        return Err(JarvisError::PythonNotFound(format!(
+            "虚拟环境中的Python解释器不存在: {}",
+            python.display()
+        )));
+    }
+    Ok(python.to_string_lossy().into_owned())
+}
+
+// 解析`python --version`的输出，返回(主版本, 次版本, 修订号)
+fn parse_python_version(text: &str) -> Option<(u32, u32, u32)> {
+    let version = text.trim().strip_prefix("Python ")?;
+    let mut parts = version.split('.').map(|part| {
+        part.chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>()
+            .parse::<u32>()
+            .ok()
+    });
+    
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().flatten().unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+// 运行Python获取版本号
+fn detect_python_version(python: &str) -> Result<(u32, u32, u32), JarvisError> {
+    let output = Command::new(python).arg("--version").output().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            JarvisError::PythonNotFound(format!(
+                "未找到Python({})，请先安装Python {}.{}或更高版本",
+                python, MIN_PYTHON_VERSION.0, MIN_PYTHON_VERSION.1
+            ))
+        } else {
+            JarvisError::SpawnFailed(format!("无法运行Python可执行文件{}: {}", python, e))
+        }
+    })?;
+    
+    // Python 2将版本信息输出到stderr
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    parse_python_version(&text).ok_or_else(|| {
+        JarvisError::PythonVersionUnsupported(format!("无法解析Python版本: {}", text.trim()))
+    })
+}
+
+// 检查Python版本是否满足最低要求
+fn ensure_python_version(python: &str) -> Result<(), JarvisError> {
+    check_min_python_version(detect_python_version(python)?)
+}
+
+// 检查已检测到的Python版本是否满足最低要求
+fn check_min_python_version(version: (u32, u32, u32)) -> Result<(), JarvisError> {
+    let (major, minor, patch) = version;
+    if (major, minor) < MIN_PYTHON_VERSION {
+        return Err(JarvisError::PythonVersionUnsupported(format!(
+            "Python版本过低: {}.{}.{}，JARVIS需要Python {}.{}或更高版本",
+            major, minor, patch, MIN_PYTHON_VERSION.0, MIN_PYTHON_VERSION.1
+        )));
+    }
+    Ok(())
+}
+
+// 解析JARVIS核心脚本路径及其工作目录
+// 未配置脚本路径时依次查找资源目录、JARVIS_CORE_DIR环境变量和当前目录的上级目录
+fn resolve_core_script(app: &AppHandle) -> Result<(PathBuf, PathBuf), JarvisError> {
+    let app_state = app.state::<AppState>();
+    if let Some(script) = lock(&app_state.core_script_path).clone() {
+        let working_dir = configured_script_dir(&script)?;
+        return Ok((script, working_dir));
+    }
+    
+    let mut candidates: Vec<(&str, PathBuf)> = Vec::new();
+    if let Ok(dir) = app.path().resource_dir() {
+        candidates.push(("资源目录", dir.join(CORE_DIR_NAME)));
+    }
+    if let Some(dir) = std::env::var_os("JARVIS_CORE_DIR").filter(|dir| !dir.is_empty()) {
+        candidates.push(("JARVIS_CORE_DIR", PathBuf::from(dir)));
+    }
+    if let Ok(root) = project_root() {
+        candidates.push(("当前目录的上级目录", root.join(CORE_DIR_NAME)));
+    }
+    
+    let (strategy, script, working_dir) = find_core_script(&candidates)?;
+    // 只在找到的脚本变化时记录，避免诊断等频繁调用重复记录
+    let mut resolved = lock(&app_state.resolved_core_script);
+    if resolved.as_ref() != Some(&script) {
+        let message = format!("使用{}中的JARVIS核心脚本: {}", strategy, script.display());
+        app_state.log_app(DEFAULT_INSTANCE, message);
+        *resolved = Some(script.clone());
+    }
+    Ok((script, working_dir))
+}
+
+// 用户配置的核心脚本以其所在目录为工作目录
+fn configured_script_dir(script: &Path) -> Result<PathBuf, JarvisError> {
+    parent_dir(script).ok_or_else(|| {
+        JarvisError::ScriptMissing(format!("无法找到JARVIS核心脚本{}所在目录", script.display()))
+    })
+}
+
+// 在候选核心目录中查找核心脚本，返回(查找方式, 脚本路径, 工作目录)
+fn find_core_script<'a>(
+    candidates: &[(&'a str, PathBuf)],
+) -> Result<(&'a str, PathBuf, PathBuf), JarvisError> {
+    for (strategy, core_dir) in candidates {
+        let script = core_dir.join(CORE_SCRIPT_NAME);
+        if script.is_file() {
+            // 工作目录为核心目录的上级目录，与项目默认布局保持一致
+            let working_dir = parent_dir(core_dir).unwrap_or_else(|| core_dir.clone());
+            return Ok((strategy, script, working_dir));
+        }
+    }
+    
+    let tried: Vec<String> = candidates
+        .iter()
+        .map(|(strategy, dir)| format!("{}({})", dir.join(CORE_SCRIPT_NAME).display(), strategy))
+        .collect();
+    Err(JarvisError::ScriptMissing(format!(
+        "未找到JARVIS核心脚本，已尝试: {}",
+        tried.join(", ")
+    )))
+}
+
+// 请求就绪检查接口，返回成功状态码即视为已就绪
+async fn is_ready(
+    client: &JarvisHttp,
+    base_url: &str,
+    readiness_path: &str,
+    timeout: Duration,
+) -> bool {
+    get_jarvis(client, base_url, readiness_path, timeout, 0)
+        .await
+        .is_ok_and(|(status, _)| status.is_success())
+}
+
+// 轮询就绪检查接口直到服务就绪，超过总超时时间则返回错误
+async fn wait_until_ready(
+    client: &JarvisHttp,
+    base_url: &str,
+    readiness_path: &str,
+    timeout: Duration,
+) -> Result<(), JarvisError> {
+    let deadline = Instant::now() + timeout;
+    let mut delay = Duration::from_millis(200);
+    
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if is_ready(client, base_url, readiness_path, remaining.min(DEFAULT_STATUS_TIMEOUT)).await {
+            return Ok(());
+        }
+        
+        if Instant::now() + delay >= deadline {
+            return Err(JarvisError::Timeout(format!(
+                "等待JARVIS服务就绪超时({}秒)",
+                timeout.as_secs()
+            )));
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_READY_POLL_DELAY);
+    }
+}
+
+// 等待主窗口创建，超时后仍继续，避免窗口异常时永远不启动服务
+async fn wait_for_main_window(app: &AppHandle) {
+    let deadline = Instant::now() + MAIN_WINDOW_TIMEOUT;
+    while app.get_webview_window("main").is_none() && Instant::now() < deadline {
+        tokio::time::sleep(MAIN_WINDOW_POLL_INTERVAL).await;
+    }
+}
+
+// 自动启动所处阶段
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum AutostartStage {
+    CheckingPython,
+    CheckingDeps,
+    Spawning,
+    WaitingReady,
+    Ready,
+    Failed,
+}
+
+// autostart-stage事件内容，detail为附加说明，failed阶段附带错误信息
+#[derive(Clone, Serialize)]
+struct AutostartStageEvent {
+    stage: AutostartStage,
+    detail: Option<String>,
+    error: Option<JarvisError>,
+}
+
+fn emit_autostart_stage(
+    app: &AppHandle,
+    stage: AutostartStage,
+    detail: Option<String>,
+    error: Option<JarvisError>,
+) {
+    let _ = app.emit("autostart-stage", AutostartStageEvent { stage, detail, error });
+}
+
+// 应用启动时自动启动默认实例并等待其就绪，失败时重试，全部失败后通知前端
+// 各阶段通过autostart-stage事件通知前端，便于显示启动进度
+async fn auto_start_service(app: AppHandle) {
+    wait_for_main_window(&app).await;
+    
+    let delay = *lock(&app.state::<AppState>().startup_delay_ms);
+    if delay > 0 {
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+    }
+    
+    let instance = app.state::<AppState>().default_instance();
+    if !instance.is_remote() {
+        emit_autostart_stage(&app, AutostartStage::CheckingPython, None, None);
+        let python = resolve_python_executable(&app.state::<AppState>());
+        let checked = run_blocking(move || ensure_python_version(&python?)).await;
+        if let Err(e) = checked {
+            lifecycle::record_error(&app, &instance.name, &e);
+            emit_autostart_stage(&app, AutostartStage::Failed, None, Some(e.clone()));
+            let _ = app.emit("jarvis-autostart-failed", Some(e));
+            return;
+        }
+        
+        // 依赖检查仅用于提示，缺少依赖时仍尝试启动
+        emit_autostart_stage(&app, AutostartStage::CheckingDeps, None, None);
+        match check_dependencies(app.clone(), app.state::<AppState>()).await {
+            Ok(report) if !report.missing.is_empty() => {
+                let missing: Vec<&str> =
+                    report.missing.iter().map(|package| package.name.as_str()).collect();
+                let detail = format!("缺少Python依赖: {}", missing.join(", "));
+                emit_autostart_stage(&app, AutostartStage::CheckingDeps, Some(detail), None);
+            }
+            Ok(_) => {}
+            Err(e) => app
+                .state::<AppState>()
+                .log_app(&instance.name, format!("自动启动前检查Python依赖失败: {}", e)),
+        }
+    }
+    
+    let mut last_error = None;
+    for attempt in 1..=AUTOSTART_MAX_ATTEMPTS {
+        let detail = Some(format!("第{}次尝试", attempt));
+        emit_autostart_stage(&app, AutostartStage::Spawning, detail, None);
+        let app_state = app.state::<AppState>();
+        let result = match start_jarvis_service(app.clone(), app_state, None, None).await {
+            Ok(_) => {
+                emit_autostart_stage(&app, AutostartStage::WaitingReady, None, None);
+                wait_ready_or_abort(&app, instance.clone()).await
+            }
+            Err(e) => Err(e),
+        };
+        
+        match result {
+            Ok(()) => {
+                emit_autostart_stage(&app, AutostartStage::Ready, None, None);
+                return;
+            }
+            // 用户在自动启动期间停止了服务，不再重试
+            Err(JarvisError::Superseded(message)) => {
+                let message = format!("自动启动已取消: {}", message);
+                app.state::<AppState>().log_app(&instance.name, message);
+                return;
+            }
+            Err(e) => {
+                let message = format!("自动启动JARVIS服务失败(第{}次): {}", attempt, e);
+                app.state::<AppState>().log_app(&instance.name, message);
+                last_error = Some(e);
+            }
+        }
+        
+        if attempt < AUTOSTART_MAX_ATTEMPTS {
+            tokio::time::sleep(AUTOSTART_RETRY_DELAY).await;
+        }
+    }
+    
+    emit_autostart_stage(&app, AutostartStage::Failed, None, last_error.clone());
+    let _ = app.emit("jarvis-autostart-failed", last_error);
+}
+
+// 在阻塞线程池中执行会持有进程锁或访问文件系统的操作，避免阻塞异步运行时
+async fn run_blocking<T, F>(f: F) -> Result<T, JarvisError>
+where
+    F: FnOnce() -> Result<T, JarvisError> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| JarvisError::Internal(format!("后台任务执行失败: {}", e)))?
+}
+
+// 启动实例的核心进程及守护线程，实例已在运行时返回false
+fn start_instance(app: &AppHandle, instance: &Arc<ServiceInstance>) -> Result<bool, JarvisError> {
+    let _operation = lock(&instance.process_op);
+    
+    // 检查是否已经在运行(包括重新连接的独立进程)
+    if instance.has_process() {
+        return Ok(false);
+    }
+    
+    // 清空该实例上一次运行的日志
+    lock(&app.state::<AppState>().log_buffer).retain(|entry| entry.instance != instance.name);
+    
+    emit_lifecycle(app, &instance.name, JarvisLifecycle::Starting, None);
+    match spawn_jarvis_process(app, instance) {
+        Ok(child) => instance.store_process(child),
+        Err(e) => {
+            emit_failure(app, &instance.name, JarvisLifecycle::Failed, &e);
+            return Err(e);
+        }
+    }
+    
+    // 更新运行状态
+    instance.set_running(true);
+    *lock(&instance.restart_count) = 0;
+    emit_lifecycle(app, &instance.name, JarvisLifecycle::Started, None);
+    
+    // 启动进程守护线程
+    spawn_supervisor(app.clone(), instance.clone());
+    Ok(true)
+}
+
+// 串行执行实例的启动/停止/重启命令，并合并排队期间的请求
+// 排队中的多个请求只执行最后一个(例如启动-停止-启动最终只执行一次启动)，
+// 合并的启动请求中只要有一个要求等待就绪就会等待；
+// 所有排队的调用方都得到最终执行的结果，最终执行的操作与自身请求不同时返回superseded错误
+async fn run_lifecycle(
+    app: &AppHandle,
+    instance: &Arc<ServiceInstance>,
+    intent: LifecycleIntent,
+) -> Result<String, JarvisError> {
+    instance
+        .lifecycle
+        .run(intent, |target| apply_lifecycle(app, instance, target))
+        .await
+}
+
+// 执行单个生命周期命令
+async fn apply_lifecycle(
+    app: &AppHandle,
+    instance: &Arc<ServiceInstance>,
+    intent: LifecycleIntent,
+) -> Result<String, JarvisError> {
+    match intent {
+        LifecycleIntent::Start { wait_for_ready } => {
+            start_and_wait(app, instance, wait_for_ready).await
+        }
+        LifecycleIntent::Stop { grace } => {
+            let app = app.clone();
+            let instance = instance.clone();
+            run_blocking(move || stop_service(&app, &instance, grace)).await
+        }
+        LifecycleIntent::Restart => {
+            let app = app.clone();
+            let instance = instance.clone();
+            run_blocking(move || restart_instance(&app, &instance)).await?;
+            Ok("JARVIS服务重启成功".to_string())
+        }
+    }
+}
+
+// 启动Python JARVIS核心服务，wait_for_ready为true时等到服务可用后才返回
+// 与停止、重启命令串行执行，启动请求可能被之后的停止请求取代(返回superseded错误)
+// 等待就绪期间命令队列一直被占用，之后排队的停止请求最长要等待ready_timeout才会执行
+#[tauri::command]
+async fn start_jarvis_service(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    wait_for_ready: Option<bool>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let name = instance_name(instance)?;
+    let instance = app_state.instance(&name);
+    
+    // 远程模式下由外部负责运行服务
+    if instance.is_remote() {
+        return Ok("远程模式下无需启动本地JARVIS服务".to_string());
+    }
+    
+    // 仅默认实例参与应用启动时的自动启动
+    if name == DEFAULT_INSTANCE {
+        if let Err(e) = config::save_run_intent(&app, true) {
+            app_state.log_app(DEFAULT_INSTANCE, e);
+        }
+    }
+    
+    let intent = LifecycleIntent::Start {
+        wait_for_ready: wait_for_ready.unwrap_or(false),
+    };
+    run_lifecycle(&app, &instance, intent).await
+}
+
+// 启动实例，wait_for_ready为true时等待服务就绪
+async fn start_and_wait(
+    app: &AppHandle,
+    instance: &Arc<ServiceInstance>,
+    wait_for_ready: bool,
+) -> Result<String, JarvisError> {
+    let spawn_started = Instant::now();
+    let started = {
+        let app = app.clone();
+        let instance = instance.clone();
+        run_blocking(move || start_instance(&app, &instance)).await?
+    };
+    if !started {
+        return Ok("JARVIS服务已在运行".to_string());
+    }
+    let spawn_ms = spawn_started.elapsed().as_millis() as u64;
+    *lock(&instance.spawned) = Some((Instant::now(), spawn_ms));
+    
+    if !wait_for_ready {
+        return Ok("JARVIS服务启动成功".to_string());
+    }
+    
+    wait_ready_or_abort(app, instance.clone()).await?;
+    Ok("JARVIS服务启动成功，已就绪".to_string())
+}
+
+// 等待刚启动的实例就绪，超时则结束启动到一半的进程
+async fn wait_ready_or_abort(app: &AppHandle, instance: Arc<ServiceInstance>) -> Result<(), JarvisError> {
+    let app_state = app.state::<AppState>();
+    let base_url = instance.base_url();
+    let readiness_path = lock(&app_state.readiness_path).clone();
+    let client = app_state.jarvis_http(&instance);
+    let timeout = app_state.ready_timeout();
+    let Err(e) = wait_until_ready(&client, &base_url, &readiness_path, timeout).await else {
+        record_start_timing(app, &instance);
+        return Ok(());
+    };
+    
+    let name = instance.name.clone();
+    {
+        let app = app.clone();
+        run_blocking(move || {
+            let _operation = lock(&instance.process_op);
+            instance.set_running(false);
+            if let Some(mut child) = instance.take_process() {
+                let _ = child.kill();
+                if let Ok(status) = wait_for_exit(&mut child, PROCESS_EXIT_TIMEOUT) {
+                    *lock(&instance.last_exit) = Some(ExitInfo::from_status(status));
+                }
+            }
+            pidfile::remove_pid(&app, &instance.name);
+            Ok(())
+        })
+        .await?;
+    }
+    emit_failure(app, &name, JarvisLifecycle::Failed, &e);
+    Err(e)
+}
+
+// 记录本次启动的耗时并发送jarvis-started-timing事件，只在start_jarvis_service启动的进程首次就绪时记录
+fn record_start_timing(app: &AppHandle, instance: &ServiceInstance) {
+    let Some((spawned_at, spawn_ms)) = lock(&instance.spawned).take() else {
+        return;
+    };
+    
+    let timing = metrics::StartTiming {
+        instance: instance.name.clone(),
+        spawn_ms,
+        ready_ms: spawned_at.elapsed().as_millis() as u64,
+        timestamp_ms: logs::unix_millis(),
+    };
+    let _ = app.emit("jarvis-started-timing", &timing);
+    lock(&app.state::<AppState>().start_timings).record(timing);
+}
+
+// 获取实例最近几次启动的耗时统计(创建进程和等待就绪的最小、最大、平均值)
+#[tauri::command]
+async fn get_timing_metrics(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<metrics::TimingStats, JarvisError> {
+    let name = instance_name(instance)?;
+    let stats = lock(&app_state.start_timings).stats(&name);
+    Ok(stats)
+}
+
+// 只执行启动前的检查而不启动进程，返回将要使用的程序、脚本路径和端口，或第一个未通过的检查
+#[tauri::command]
+async fn validate_start(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<LaunchPlan, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    if instance.is_remote() {
+        return Err(JarvisError::RemoteMode("远程模式下无需启动本地JARVIS服务".to_string()));
+    }
+    if instance.has_process() {
+        return Err(JarvisError::AlreadyRunning("JARVIS服务已在运行".to_string()));
+    }
+    
+    run_blocking(move || plan_launch(&app, &instance)).await
+}
+
+// 停止实例的JARVIS核心进程，不记录用户意图(供窗口关闭等非用户主动停止的场景使用)
+fn stop_service(app: &AppHandle, instance: &ServiceInstance, grace: Duration) -> Result<String, JarvisError> {
+    let _operation = lock(&instance.process_op);
+    
+    // 先清除运行标记，避免守护线程在停止过程中重新拉起进程
+    instance.set_running(false);
+    
+    let Some(mut child) = instance.take_process() else {
+        return stop_adopted_process(app, instance, grace);
+    };
+    
+    emit_lifecycle(app, &instance.name, JarvisLifecycle::Stopping, None);
+    match shutdown_process(&mut child, grace) {
+        Ok((kind, status)) => {
+            *lock(&instance.last_exit) = Some(ExitInfo::from_status(status));
+            pidfile::remove_pid(app, &instance.name);
+            let message = match kind {
+                ShutdownKind::Graceful => "JARVIS服务已正常停止",
+                ShutdownKind::Forced => "JARVIS服务未能正常退出，已强制停止",
+            };
+            emit_lifecycle(app, &instance.name, JarvisLifecycle::Stopped, Some(message.to_string()));
+            Ok(message.to_string())
+        }
+        Err(e) => {
+            instance.store_process(child);
+            emit_failure(app, &instance.name, JarvisLifecycle::Failed, &e);
+            Err(e)
+        }
+    }
+}
+
+// 按PID文件中的PID停止重新连接的独立进程(没有Child，也无法获取退出状态)
+// grace为等待正常退出的时间，为0时直接强制结束
+fn stop_adopted_process(
+    app: &AppHandle,
+    instance: &ServiceInstance,
+    grace: Duration,
+) -> Result<String, JarvisError> {
+    let Some(pid) = instance.adopted_pid() else {
+        return Ok("JARVIS服务未在运行".to_string());
+    };
+    
+    emit_lifecycle(app, &instance.name, JarvisLifecycle::Stopping, None);
+    match pidfile::kill_pid(pid, grace) {
+        Ok(()) => {
+            *lock(&instance.detached_pid) = None;
+            pidfile::remove_pid(app, &instance.name);
+            let message = format!("已停止独立运行的JARVIS进程(PID {})", pid);
+            emit_lifecycle(app, &instance.name, JarvisLifecycle::Stopped, Some(message.clone()));
+            Ok(message)
+        }
+        Err(e) => {
+            let error = JarvisError::ProcessControlFailed(e);
+            emit_failure(app, &instance.name, JarvisLifecycle::Failed, &error);
+            Err(error)
+        }
+    }
+}
+
+// 停止Python JARVIS核心服务，grace_secs为等待正常退出的宽限期
+// 与启动、重启命令串行执行，排队期间可能被之后的启动请求取代
+#[tauri::command]
+async fn stop_jarvis_service(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    grace_secs: Option<u64>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    
+    if instance.name == DEFAULT_INSTANCE {
+        if let Err(e) = config::save_run_intent(&app, false) {
+            app_state.log_app(DEFAULT_INSTANCE, e);
+        }
+    }
+    
+    let grace = grace_secs.map_or(app_state.shutdown_grace(), |secs| {
+        Duration::from_secs(secs.min(MAX_SHUTDOWN_GRACE_SECS))
+    });
+    run_lifecycle(&app, &instance, LifecycleIntent::Stop { grace }).await
+}
+
+// 立即强制结束JARVIS核心进程及其子进程，用于正常停止无响应的情况
+#[tauri::command]
+async fn force_kill_jarvis(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    
+    if instance.name == DEFAULT_INSTANCE {
+        if let Err(e) = config::save_run_intent(&app, false) {
+            app_state.log_app(DEFAULT_INSTANCE, e);
+        }
+    }
+    
+    run_blocking(move || {
+        let _operation = lock(&instance.process_op);
+        instance.set_running(false);
+        
+        let Some(mut child) = instance.take_process() else {
+            return stop_adopted_process(&app, &instance, Duration::ZERO);
+        };
+        let pid = child.id();
+        emit_lifecycle(&app, &instance.name, JarvisLifecycle::Stopping, None);
+        
+        let result = kill_process_tree(&mut child)
+            .and_then(|_| wait_for_exit(&mut child, FORCE_KILL_TIMEOUT));
+        match result {
+            Ok(status) => {
+                *lock(&instance.last_exit) = Some(ExitInfo::from_status(status));
+                pidfile::remove_pid(&app, &instance.name);
+                let message = format!("已强制结束JARVIS进程(PID {})", pid);
+                emit_lifecycle(&app, &instance.name, JarvisLifecycle::Stopped, Some(message.clone()));
+                Ok(message)
+            }
+            Err(e) => {
+                instance.store_process(child);
+                let error = JarvisError::ProcessControlFailed(format!(
+                    "JARVIS进程(PID {})在强制结束{}秒后仍未退出: {}",
+                    pid,
+                    FORCE_KILL_TIMEOUT.as_secs(),
+                    e
+                ));
+                emit_failure(&app, &instance.name, JarvisLifecycle::Failed, &error);
+                Err(error)
+            }
+        }
+    })
+    .await
+}
+
+// 停止实例的旧进程并启动新进程
+fn restart_instance(app: &AppHandle, instance: &Arc<ServiceInstance>) -> Result<(), JarvisError> {
+    let _operation = lock(&instance.process_op);
+    
+    // 先停止旧进程，确认退出后再启动新进程
+    if !instance.has_child() && instance.adopted_pid().is_some() {
+        instance.set_running(false);
+        stop_adopted_process(app, instance, app.state::<AppState>().shutdown_grace())?;
+    }
+    if let Some(mut child) = instance.take_process() {
+        instance.set_running(false);
+        emit_lifecycle(app, &instance.name, JarvisLifecycle::Stopping, Some("重启".to_string()));
+        
+        let grace = app.state::<AppState>().shutdown_grace();
+        match shutdown_process(&mut child, grace) {
+            Ok((_, status)) => {
+                *lock(&instance.last_exit) = Some(ExitInfo::from_status(status));
+                emit_lifecycle(app, &instance.name, JarvisLifecycle::Stopped, Some("重启".to_string()));
+            }
+            Err(e) => {
+                instance.store_process(child);
+                emit_failure(app, &instance.name, JarvisLifecycle::Failed, &e);
+                return Err(e);
+            }
+        }
+    }
+    
+    emit_lifecycle(app, &instance.name, JarvisLifecycle::Starting, Some("重启".to_string()));
+    match spawn_jarvis_process(app, instance) {
+        Ok(child) => instance.store_process(child),
+        Err(e) => {
+            emit_failure(app, &instance.name, JarvisLifecycle::Failed, &e);
+            return Err(e);
+        }
+    }
+    
+    // 新进程启动成功后才更新运行状态
+    instance.set_running(true);
+    *lock(&instance.restart_count) = 0;
+    emit_lifecycle(app, &instance.name, JarvisLifecycle::Started, None);
+    spawn_supervisor(app.clone(), instance.clone());
+    
+    Ok(())
+}
+
+// 重启Python JARVIS核心服务，与启动、停止命令串行执行
+#[tauri::command]
+async fn restart_jarvis_service(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    if instance.is_remote() {
+        return Ok("远程模式下无需重启本地JARVIS服务".to_string());
+    }
+    
+    if instance.name == DEFAULT_INSTANCE {
+        if let Err(e) = config::save_run_intent(&app, true) {
+            app_state.log_app(DEFAULT_INSTANCE, e);
+        }
+    }
+    
+    run_lifecycle(&app, &instance, LifecycleIntent::Restart).await
+}
+
+// 不重启进程的情况下让核心重新加载配置和模型，方式由set_reload_mechanism设置
+// 重新加载后确认核心进程仍在运行且健康检查通过，否则返回错误
+#[tauri::command]
+async fn reload_jarvis(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let mechanism = *lock(&app_state.reload_mechanism);
+    let http = app_state.jarvis_http(&instance);
+    let base_url = instance.base_url();
+    
+    match mechanism {
+        ReloadMechanism::Signal => {
+            if instance.is_remote() {
+                return Err(JarvisError::RemoteMode(
+                    "远程模式下无法向核心发送信号，请改用接口方式".to_string(),
+                ));
+            }
+            // 应用启动时重新连接的独立进程没有Child，同样按PID发送
+            let Some(pid) = instance.pid() else {
+                return Err(JarvisError::NotRunning("JARVIS服务未运行".to_string()));
+            };
+            send_reload_signal(pid)?;
+        }
+        ReloadMechanism::Endpoint => {
+            let response = http
+                .post(jarvis_url(&base_url, RELOAD_PATH))
+                .timeout(DEFAULT_STATUS_TIMEOUT)
+                .send()
+                .await
+                .map_err(|e| JarvisError::HttpError(format!("请求重新加载失败: {}", e)))?;
+            let status = response.status();
+            check_auth(status)?;
+            if status == reqwest::StatusCode::NOT_FOUND {
+                return Err(JarvisError::NotSupported(
+                    "当前JARVIS核心版本不支持重新加载(/reload接口不存在)".to_string(),
+                ));
+            }
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(JarvisError::HttpError(format!(
+                    "重新加载失败: {} {}",
+                    status,
+                    body.trim()
+                )));
+            }
+        }
+    }
+    
+    // 核心不支持重新加载时可能直接退出，稍等片刻再确认进程状态
+    tokio::time::sleep(RELOAD_SETTLE_DELAY).await;
+    if !instance.is_remote() && instance.pid().is_none() {
+        return Err(JarvisError::ProcessControlFailed(
+            "重新加载后JARVIS服务进程已退出，核心可能不支持重新加载".to_string(),
+        ));
+    }
+    
+    let readiness_path = lock(&app_state.readiness_path).clone();
+    let timeout = app_state.ready_timeout();
+    wait_until_ready(&http, &base_url, &readiness_path, timeout)
+        .await
+        .map_err(|e| JarvisError::Timeout(format!("重新加载后JARVIS服务未恢复: {}", e)))?;
+    Ok("JARVIS服务已重新加载".to_string())
+}
+
+// 设置重新加载核心的方式，非Unix系统只支持接口方式
+#[tauri::command]
+async fn set_reload_mechanism(
+    app_state: tauri::State<'_, AppState>,
+    mechanism: ReloadMechanism,
+) -> Result<String, JarvisError> {
+    if mechanism == ReloadMechanism::Signal && !cfg!(unix) {
+        return Err(JarvisError::NotSupported("当前系统不支持通过信号重新加载".to_string()));
+    }
+    
+    *lock(&app_state.reload_mechanism) = mechanism;
+    match mechanism {
+        ReloadMechanism::Signal => Ok("重新加载方式已设置为SIGHUP信号".to_string()),
+        ReloadMechanism::Endpoint => Ok(format!("重新加载方式已设置为{}接口", RELOAD_PATH)),
+    }
+}
+
+// restart_if_unhealthy的结果，restarted为false表示服务正常、未做处理
+// reason为判定不健康的原因，ready表示重启后服务是否在就绪超时内恢复
+#[derive(Debug, Clone, Serialize)]
+struct RestartResult {
+    restarted: bool,
+    reason: Option<String>,
+    ready: bool,
+    message: String,
+}
+
+// 先做一次健康检查，仅在服务不可用或卡死(超过判定时长没有成功的健康检查)时重启
+// 重启后等待服务就绪并报告结果，适合作为一键修复
+#[tauri::command]
+async fn restart_if_unhealthy(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<RestartResult, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    if instance.is_remote() {
+        return Err(JarvisError::RemoteMode("远程模式下无法重启JARVIS服务".to_string()));
+    }
+    
+    let base_url = instance.base_url();
+    let health_path = lock(&app_state.health_path).clone();
+    let client = app_state.jarvis_http(&instance);
+    let stall_threshold = *lock(&app_state.stall_threshold_secs);
+    let check =
+        fetch_jarvis_status(&client, &base_url, &health_path, DEFAULT_STATUS_TIMEOUT, 0).await;
+    let reason = match check {
+        Ok(_) => {
+            instance.mark_healthy();
+            return Ok(RestartResult {
+                restarted: false,
+                reason: None,
+                ready: true,
+                message: "JARVIS服务运行正常，无需重启".to_string(),
+            });
+        }
+        Err(e) => match instance.stalled_seconds(stall_threshold) {
+            Some(secs) => {
+                format!("{}秒内没有成功的健康检查，服务可能已卡死: {}", secs, e)
+            }
+            _ => format!("健康检查失败: {}", e),
+        },
+    };
+    
+    if instance.name == DEFAULT_INSTANCE {
+        if let Err(e) = config::save_run_intent(&app, true) {
+            app_state.log_app(DEFAULT_INSTANCE, e);
+        }
+    }
+    run_lifecycle(&app, &instance, LifecycleIntent::Restart).await?;
+    
+    let readiness_path = lock(&app_state.readiness_path).clone();
+    let timeout = app_state.ready_timeout();
+    let ready = wait_until_ready(&client, &base_url, &readiness_path, timeout).await;
+    let message = match &ready {
+        Ok(()) => "JARVIS服务已重启并恢复正常".to_string(),
+        Err(e) => format!("JARVIS服务已重启，但尚未就绪: {}", e),
+    };
+    Ok(RestartResult {
+        restarted: true,
+        reason: Some(reason),
+        ready: ready.is_ok(),
+        message,
+    })
 }
 
-// JARVIS状态检查命令
+// 获取JARVIS服务运行状态
 #[tauri::command]
-async fn check_jarvis_status() -> Result<Value, String> {
-    let client = reqwest::Client::new();
+async fn get_jarvis_running_status(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<bool, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
     
-    match client
-        .get("http://127.0.0.1:8000/status")
-        .timeout(Duration::from_secs(5))
-        .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.json::<Value>().await {
-                    Ok(json) => Ok(json),
-                    Err(e) => Err(format!("解析响应失败: {}", e)),
-                }
-            } else {
-                Err(format!("JARVIS服务响应错误: {}", response.status()))
-            }
+    // 远程模式下以服务是否可访问作为运行状态
+    if instance.is_remote() {
+        let base_url = instance.base_url();
+        let health_path = lock(&app_state.health_path).clone();
+        let client = &app_state.jarvis_http(&instance);
+        let status =
+            fetch_jarvis_status(client, &base_url, &health_path, DEFAULT_STATUS_TIMEOUT, 0).await;
+        return Ok(status.is_ok());
+    }
+    
+    let is_running = instance.is_running();
+    Ok(is_running)
+}
+
+// 切换实例的后端模式，远程模式需提供服务地址(如http://192.168.1.10:8000)
+#[tauri::command]
+async fn set_backend_mode(
+    app_state: tauri::State<'_, AppState>,
+    mode: BackendMode,
+    base_url: Option<String>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.instance(&instance_name(instance)?);
+    if instance.has_process() {
+        return Err(JarvisError::AlreadyRunning(
+            "JARVIS服务正在运行，请先停止服务再切换后端模式".to_string(),
+        ));
+    }
+    
+    match mode {
+        BackendMode::Local => {
+            *lock(&instance.remote_base_url) = None;
+            Ok("已切换为本地模式".to_string())
+        }
+        BackendMode::Remote => {
+            let url = base_url
+                .ok_or_else(|| JarvisError::InvalidArgument("远程模式需要提供服务地址".to_string()))?;
+            let url = normalize_remote_url(&url)?;
+            let message = format!("已切换为远程模式: {}", url);
+            *lock(&instance.remote_base_url) = Some(url);
+            instance.reset_health_window();
+            Ok(message)
         }
-        Err(e) => Err(format!("连接JARVIS服务失败: {}", e)),
     }
 }
 
-// 启动Python JARVIS核心服务
+// 获取最近一次启动失败或崩溃的信息，应用启动阶段自动启动失败时前端可能错过相关事件
 #[tauri::command]
-async fn start_jarvis_service(app_state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let mut process_guard = app_state.python_process.lock().unwrap();
+async fn get_last_error(app_state: tauri::State<'_, AppState>) -> Result<Option<ErrorInfo>, JarvisError> {
+    let last_error = lock(&app_state.last_error).clone();
+    Ok(last_error)
+}
+
+// 获取实例进程最近一次退出的信息
+#[tauri::command]
+async fn get_last_exit_status(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<Option<ExitInfo>, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let last_exit = lock(&instance.last_exit).clone();
+    Ok(last_exit)
+}
+
+// 获取核心进程的CPU、内存及线程数
+#[tauri::command]
+async fn get_jarvis_resources(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<resources::ResourceStats, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let not_running = || JarvisError::NotRunning("JARVIS服务未在运行".to_string());
     
-    // 检查是否已经在运行
-    if process_guard.is_some() {
-        return Ok("JARVIS服务已在运行".to_string());
+    let pid = instance.pid().ok_or_else(not_running)?;
+    let mut system = lock(&app_state.system_monitor);
+    resources::sample(&mut system, pid).ok_or_else(not_running)
+}
+
+// 列出所有已注册的实例及其状态
+#[tauri::command]
+async fn list_instances(app_state: tauri::State<'_, AppState>) -> Result<Vec<InstanceInfo>, JarvisError> {
+    let mut instances: Vec<InstanceInfo> = app_state
+        .all_instances()
+        .iter()
+        .map(|instance| InstanceInfo {
+            name: instance.name.clone(),
+            port: *lock(&instance.port),
+            running: instance.is_running(),
+            pid: instance.pid(),
+            remote_base_url: lock(&instance.remote_base_url).clone(),
+        })
+        .collect();
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(instances)
+}
+
+// 获取JARVIS进程PID，进程未运行或已退出时返回None
+#[tauri::command]
+async fn get_jarvis_pid(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<Option<u32>, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    Ok(instance.pid())
+}
+
+// 获取最近捕获的若干行服务日志
+#[tauri::command]
+fn get_recent_logs(app_state: tauri::State<'_, AppState>, lines: usize) -> Vec<LogEntry> {
+    logs::recent_logs(&lock(&app_state.log_buffer), lines)
+}
+
+// 在系统文件管理器中打开日志目录
+#[tauri::command]
+async fn open_log_dir(app: AppHandle) -> Result<String, JarvisError> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| JarvisError::Io(format!("获取应用日志目录失败: {}", e)))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| JarvisError::Io(format!("创建日志目录{}失败: {}", dir.display(), e)))?;
+    
+    let dir = dir.to_string_lossy().into_owned();
+    app.opener()
+        .open_path(dir.clone(), None::<&str>)
+        .map_err(|e| JarvisError::Io(format!("打开日志目录{}失败: {}", dir, e)))?;
+    Ok(dir)
+}
+
+// 设置实时推送日志的最低级别(DEBUG/INFO/WARNING/ERROR/CRITICAL)
+// 只影响jarvis-stdout/jarvis-stderr事件，get_recent_logs仍返回全部日志
+#[tauri::command]
+async fn set_log_filter(app_state: tauri::State<'_, AppState>, level: String) -> Result<String, JarvisError> {
+    if LogLevel::parse(&level).is_none() {
+        return Err(JarvisError::InvalidArgument(format!("无效的日志级别: {}", level)));
     }
     
-    // 启动Python服务
-    let python_executable = if cfg!(windows) {
-        "python"
-    } else {
-        "python3"
+    let level = level.trim().to_ascii_uppercase();
+    *lock(&app_state.log_level_filter) = level.clone();
+    Ok(format!("实时日志级别已设置为{}", level))
+}
+
+// 获取自动重启次数
+#[tauri::command]
+async fn get_restart_count(
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<u32, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    let restart_count = *lock(&instance.restart_count);
+    Ok(restart_count)
+}
+
+// 创建Python虚拟环境，成功后后续启动和依赖安装均使用该环境
+#[tauri::command]
+async fn create_venv(app_state: tauri::State<'_, AppState>, path: String) -> Result<String, JarvisError> {
+    let python_executable = base_python_executable(&app_state);
+    let venv = PathBuf::from(&path);
+    
+    let output = {
+        let venv = venv.clone();
+        run_blocking(move || {
+            Command::new(&python_executable)
+                .args(["-m", "venv"])
+                .arg(&venv)
+                .envs(PYTHON_UTF8_ENV)
+                .output()
+                .map_err(|e| JarvisError::SpawnFailed(format!("执行python -m venv失败: {}", e)))
+        })
+        .await?
+    };
+    
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(JarvisError::VenvFailed(format!("创建虚拟环境失败: {}", stderr)));
+    }
+    
+    if !venv_python(&venv).is_file() {
+        return Err(JarvisError::VenvFailed(format!(
+            "虚拟环境中的Python解释器不存在: {}",
+            venv_python(&venv).display()
+        )));
+    }
+    
+    *lock(&app_state.venv_path) = Some(venv);
+    Ok(format!("虚拟环境已创建: {}", path))
+}
+
+// 所有实例都为远程模式时不使用本地Python环境
+fn ensure_local_backend(app_state: &AppState) -> Result<(), JarvisError> {
+    if app_state.all_instances().iter().all(|instance| instance.is_remote()) {
+        return Err(JarvisError::RemoteMode("远程模式下不管理本地Python环境".to_string()));
+    }
+    Ok(())
+}
+
+// 安装Python依赖，安装过程中通过pip-progress事件实时推送pip输出
+// 所有实例都为远程模式时不可用
+// 在服务运行时升级依赖可能导致正在运行的核心进程出错，因此默认拒绝，force为true时强制安装
+// requirements_file为相对于项目目录的依赖文件(如requirements-dev.txt)，未指定时使用requirements.txt
+#[tauri::command]
+async fn install_python_dependencies(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    force: Option<bool>,
+    requirements_file: Option<String>,
+) -> Result<String, JarvisError> {
+    ensure_local_backend(&app_state)?;
+    ensure_no_local_running(&app_state, force)?;
+    let python_executable = resolve_python_executable(&app_state)?;
+    let (requirements_path, root) = match requirements_file.as_deref() {
+        Some(file) => resolve_requirements_file(&app, file)?,
+        None => resolve_requirements(&app)?,
+    };
+    let file_name = requirements_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| REQUIREMENTS_FILE_NAME.to_string());
+    
+    // 空文件或只有注释时pip会直接"成功"，这里提前报错
+    let requirements = deps::read_requirements(&requirements_path).map_err(JarvisError::Io)?;
+    if requirements.is_empty() {
+        return Err(JarvisError::RequirementsMissing(format!(
+            "{}中没有任何依赖声明",
+            file_name
+        )));
+    }
+    // 版本约束可疑时仍尝试安装，警告通过应用日志事件立即推送，并附在返回结果中
+    let invalid = deps::invalid_specifiers(&requirements);
+    let warning = (!invalid.is_empty())
+        .then(|| format!("{}中以下依赖的版本约束可能无效: {}", file_name, invalid.join(", ")));
+    if let Some(warning) = &warning {
+        app_state.log_app(DEFAULT_INSTANCE, warning.clone());
+    }
+    
+    let mut command = tokio::process::Command::new(python_executable);
+    command
+        .args(["-m", "pip", "install", "--progress-bar", "off", "-r"])
+        .arg(&requirements_path)
+        .current_dir(root);
+    let (status, stderr) = run_pip_install(&app, &app_state, command, requirements.len()).await?;
+    
+    let count = requirements.len();
+    match (status.success(), warning) {
+        (true, None) => Ok(format!("Python依赖安装成功，共{}个依赖", count)),
+        (true, Some(warning)) => Ok(format!("Python依赖安装成功，共{}个依赖；{}", count, warning)),
+        (false, None) => Err(JarvisError::PipFailed(format!("Python依赖安装失败: {}", stderr))),
+        (false, Some(warning)) => Err(JarvisError::PipFailed(format!(
+            "Python依赖安装失败({}): {}",
+            warning, stderr
+        ))),
+    }
+}
+
+// 升级单个Python包，version为空时升级到最新版本，输出同样通过pip-progress事件推送
+// 与install_python_dependencies共用运行中检查和取消机制
+#[tauri::command]
+async fn upgrade_package(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    name: String,
+    version: Option<String>,
+    force: Option<bool>,
+) -> Result<String, JarvisError> {
+    ensure_local_backend(&app_state)?;
+    let name = name.trim();
+    if !deps::is_valid_package_name(name) {
+        return Err(JarvisError::InvalidArgument(format!("无效的包名: {}", name)));
+    }
+    let spec = match version.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(version) if deps::is_valid_version(version) => format!("{}=={}", name, version),
+        Some(version) => {
+            return Err(JarvisError::InvalidArgument(format!("无效的版本号: {}", version)));
+        }
+        None => name.to_string(),
     };
+    ensure_no_local_running(&app_state, force)?;
+    let python_executable = resolve_python_executable(&app_state)?;
     
-    // 尝试启动JARVIS核心服务
-    let jarvis_core_path = std::env::current_dir()
-        .map_err(|e| format!("获取当前目录失败: {}", e))?
-        .parent()
-        .ok_or("无法找到父目录")?
-        .join("jarvis-core")
-        .join("main.py");
+    let mut command = tokio::process::Command::new(python_executable);
+    command
+        .args(["-m", "pip", "install", "--progress-bar", "off", "--upgrade"])
+        .arg(&spec);
+    let (status, stderr) = run_pip_install(&app, &app_state, command, 1).await?;
     
-    if !jarvis_core_path.exists() {
-        return Err("JARVIS核心服务文件不存在".to_string());
+    if status.success() {
+        Ok(format!("{}升级成功", spec))
+    } else {
+        Err(JarvisError::PipFailed(format!("{}升级失败: {}", spec, stderr)))
     }
+}
+
+// 本地服务运行时拒绝修改Python环境，force为true时跳过检查
+fn ensure_no_local_running(app_state: &AppState, force: Option<bool>) -> Result<(), JarvisError> {
+    let running = app_state
+        .all_instances()
+        .into_iter()
+        .any(|instance| !instance.is_remote() && instance.is_running());
+    if running && !force.unwrap_or(false) {
+        return Err(JarvisError::AlreadyRunning(
+            "JARVIS服务正在运行，安装依赖可能导致其出错，请先停止服务".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// 执行pip install并通过pip-progress事件推送输出，total为预计安装的依赖数量
+// 同一时间只允许一个安装任务，支持取消和超时，返回退出状态和stderr内容
+async fn run_pip_install(
+    app: &AppHandle,
+    app_state: &AppState,
+    mut command: tokio::process::Command,
+    total: usize,
+) -> Result<(std::process::ExitStatus, String), JarvisError> {
+    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel();
+    let Some(_install) = InstallGuard::acquire(&app_state.pip_cancel, cancel_tx) else {
+        return Err(JarvisError::AlreadyRunning("Python依赖正在安装".to_string()));
+    };
+    let timeout = Duration::from_secs(*lock(&app_state.pip_install_timeout_secs));
     
-    match Command::new(python_executable)
-        .arg(jarvis_core_path)
-        .current_dir(
-            std::env::current_dir()
-                .unwrap()
-                .parent()
-                .unwrap()
-        )
+    let mut child = command
+        .envs(PYTHON_UTF8_ENV)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
+        .kill_on_drop(true)
         .spawn()
-    {
-        Ok(child) => {
-            *process_guard = Some(child);
-            
-            // 更新运行状态
-            let is_running = app_state.is_jarvis_running.clone();
-            *is_running.lock().unwrap() = true;
-            
-            // 启动状态监控线程
-            let is_running_clone = is_running.clone();
-            thread::spawn(move || {
-                thread::sleep(Duration::from_secs(3)); // 给服务启动时间
-                
-                // 检查服务是否成功启动
-                let runtime = tokio::runtime::Runtime::new().unwrap();
-                let is_available = runtime.block_on(async {
-                    reqwest::Client::new()
-                        .get("http://127.0.0.1:8000/")
-                        .timeout(Duration::from_secs(5))
-                        .send()
-                        .await
-                        .is_ok()
-                });
-                
-                if !is_available {
-                    *is_running_clone.lock().unwrap() = false;
+        .map_err(|e| JarvisError::SpawnFailed(format!("执行pip install失败: {}", e)))?;
+    
+    // 单独收集stderr，避免其缓冲区写满阻塞pip
+    let stderr = child.stderr.take();
+    let stderr_task = tauri::async_runtime::spawn(async move {
+        let mut buf = Vec::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_end(&mut buf).await;
+        }
+        String::from_utf8_lossy(&buf).into_owned()
+    });
+    
+    let stdout = child.stdout.take();
+    let outcome = {
+        let install = async {
+            if let Some(stdout) = stdout {
+                let mut progress = deps::PipProgress::new(total);
+                let mut reader = tokio::io::BufReader::new(stdout);
+                let mut buf = Vec::new();
+                loop {
+                    buf.clear();
+                    match reader.read_until(b'\n', &mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(_) => {
+                            let line = String::from_utf8_lossy(&buf).trim_end().to_string();
+                            let _ = app.emit("pip-progress", progress.update(line));
+                        }
+                    }
                 }
-            });
-            
-            Ok("JARVIS服务启动成功".to_string())
+            }
+            child.wait().await
+        };
+        
+        tokio::select! {
+            status = install => Ok(status),
+            _ = cancel_rx => Err(JarvisError::Cancelled("已取消Python依赖安装".to_string())),
+            _ = tokio::time::sleep(timeout) => Err(JarvisError::Timeout(format!(
+                "Python依赖安装超时({}秒)",
+                timeout.as_secs()
+            ))),
         }
+    };
+    
+    // 取消或超时时结束pip进程，确保可以重新安装
+    let status = match outcome {
+        Ok(status) => status,
         Err(e) => {
-            *process_guard = None;
-            Err(format!("启动JARVIS服务失败: {}", e))
+            let _ = child.kill().await;
+            return Err(e);
+        }
+    };
+    let status =
+        status.map_err(|e| JarvisError::PipFailed(format!("等待pip install完成失败: {}", e)))?;
+    let stderr = stderr_task.await.unwrap_or_default();
+    Ok((status, stderr))
+}
+
+// 取消正在进行的Python依赖安装
+#[tauri::command]
+async fn cancel_dependency_install(app_state: tauri::State<'_, AppState>) -> Result<String, JarvisError> {
+    match lock(&app_state.pip_cancel).take() {
+        Some(cancel) => {
+            let _ = cancel.send(());
+            Ok("正在取消Python依赖安装".to_string())
         }
+        None => Err(JarvisError::NotRunning("没有正在进行的Python依赖安装".to_string())),
+    }
+}
+
+// 设置Python依赖安装的总超时时间(秒)
+#[tauri::command]
+async fn set_dependency_install_timeout(
+    app_state: tauri::State<'_, AppState>,
+    secs: u64,
+) -> Result<String, JarvisError> {
+    if secs == 0 {
+        return Err(JarvisError::InvalidArgument("超时时间必须大于0".to_string()));
     }
+    
+    *lock(&app_state.pip_install_timeout_secs) = secs;
+    Ok(format!("依赖安装超时时间已设置为{}秒", secs))
 }
 
-// 停止Python JARVIS核心服务
+// 列出正在监听指定端口的进程(PID和进程名)，端口空闲时返回空列表，用于排查端口占用
 #[tauri::command]
-async fn stop_jarvis_service(app_state: tauri::State<'_, AppState>) -> Result<String, String> {
-    let mut process_guard = app_state.python_process.lock().unwrap();
+async fn who_has_port(port: u16) -> Result<Vec<ports::PortHolder>, JarvisError> {
+    validate_port(port)?;
+    run_blocking(move || ports::who_has_port(port)).await
+}
+
+// 一次性检查启动JARVIS服务所需的各项条件，供前端展示诊断面板
+#[tauri::command]
+async fn run_diagnostics(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<DiagnosticsReport, JarvisError> {
+    let instance = app_state.instance(&instance_name(instance)?);
+    let mut checks = Vec::new();
     
-    match process_guard.as_mut() {
-        Some(child) => {
-            match child.kill() {
-                Ok(_) => {
-                    let _ = child.wait(); // 等待进程完全终止
-                    *process_guard = None;
-                    *app_state.is_jarvis_running.lock().unwrap() = false;
-                    Ok("JARVIS服务已停止".to_string())
+    // 运行Python和读取文件都会阻塞，放在同一个后台任务中完成
+    let python = resolve_python_executable(&app_state);
+    let (python, script, requirements) = {
+        let app = app.clone();
+        run_blocking(move || {
+            let python = python.and_then(|python| {
+                let version = detect_python_version(&python)?;
+                check_min_python_version(version)?;
+                let (major, minor, patch) = version;
+                Ok(format!("{} (Python {}.{}.{})", python, major, minor, patch))
+            });
+            
+            let script = resolve_core_script(&app).map(|(script, _)| script.display().to_string());
+            
+            let requirements = resolve_requirements(&app).and_then(|(path, _)| {
+                match deps::read_requirements(&path).map_err(JarvisError::Io)?.len() {
+                    0 => Err(JarvisError::RequirementsMissing(
+                        "requirements.txt中没有任何依赖声明".to_string(),
+                    )),
+                    count => Ok(format!("{}，共{}个依赖", path.display(), count)),
                 }
-                Err(e) => Err(format!("停止JARVIS服务失败: {}", e)),
+            });
+            Ok((python, script, requirements))
+        })
+        .await?
+    };
+    checks.push(DiagnosticCheck::from_result("python", python));
+    checks.push(DiagnosticCheck::from_result("core_script", script));
+    checks.push(DiagnosticCheck::from_result("requirements", requirements));
+    
+    let port = *lock(&instance.port);
+    let unix_socket = lock(&instance.unix_socket).clone();
+    let port_check = if instance.is_remote() {
+        Ok("远程模式，无需检查本地端口".to_string())
+    } else if let Some(path) = unix_socket {
+        Ok(format!("使用Unix socket {}", path.display()))
+    } else if instance.is_running() {
+        Ok(format!("端口{}正由当前JARVIS服务使用", port))
+    } else {
+        let readiness_path = lock(&app_state.readiness_path).clone();
+        let addr = instance.local_addr();
+        match run_blocking(move || Ok(probe_port(addr, &readiness_path))).await? {
+            PortOccupant::Free => Ok(format!("端口{}可用", port)),
+            PortOccupant::Jarvis => Err(JarvisError::PortInUse(format!(
+                "端口{}上已有不受本应用管理的JARVIS实例在运行",
+                port
+            ))),
+            PortOccupant::Other => {
+                Err(JarvisError::PortInUse(format!("端口{}已被其他程序占用", port)))
             }
         }
-        None => Ok("JARVIS服务未在运行".to_string()),
-    }
+    };
+    checks.push(DiagnosticCheck::from_result("port", port_check));
+    
+    let base_url = instance.base_url();
+    let health_path = lock(&app_state.health_path).clone();
+    let retries = *lock(&app_state.http_retries);
+    let health = fetch_jarvis_status(
+        &app_state.jarvis_http(&instance),
+        &base_url,
+        &health_path,
+        DEFAULT_STATUS_TIMEOUT,
+        retries,
+    )
+    .await
+    .map(|_| format!("{}可访问", jarvis_url(&base_url, &health_path)));
+    checks.push(DiagnosticCheck::from_result("health", health));
+    
+    let log_file = match lock(&app_state.log_file).as_ref() {
+        Some(log_file) => Ok(log_file.path().display().to_string()),
+        None => Err(JarvisError::Io("日志文件不可用，日志仅保存在内存中".to_string())),
+    };
+    checks.push(DiagnosticCheck::from_result("log_file", log_file));
+    
+    Ok(DiagnosticsReport::new(checks))
 }
 
-// 获取JARVIS服务运行状态
+// 检查requirements.txt中的依赖是否已安装且版本匹配，不执行安装
 #[tauri::command]
-async fn get_jarvis_running_status(app_state: tauri::State<'_, AppState>) -> Result<bool, String> {
-    let is_running = *app_state.is_jarvis_running.lock().unwrap();
-    Ok(is_running)
+async fn check_dependencies(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+) -> Result<deps::DependencyReport, JarvisError> {
+    ensure_local_backend(&app_state)?;
+    let python_executable = resolve_python_executable(&app_state)?;
+    let (requirements_path, _) = resolve_requirements(&app)?;
+    let requirements = deps::read_requirements(&requirements_path).map_err(JarvisError::Io)?;
+    
+    let output = run_blocking(move || {
+        Command::new(python_executable)
+            .args(["-m", "pip", "freeze"])
+            .envs(PYTHON_UTF8_ENV)
+            .output()
+            .map_err(|e| JarvisError::SpawnFailed(format!("执行pip freeze失败: {}", e)))
+    })
+    .await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(JarvisError::PipFailed(format!("获取已安装的Python包失败: {}", stderr)));
+    }
+    
+    let installed = deps::parse_freeze(&String::from_utf8_lossy(&output.stdout));
+    Ok(deps::build_report(&requirements, &installed))
 }
 
-// 安装Python依赖
-#[tauri::command]
-async fn install_python_dependencies() -> Result<String, String> {
-    let python_executable = if cfg!(windows) {
-        "python"
-    } else {
-        "python3"
+// 独立模式下应用启动时重新连接上次启动的核心进程：PID文件中的进程仍存活且就绪检查通过时
+// 直接接管而不重新启动，返回是否已重新连接；接管的进程不受守护线程监控，意外退出后不会自动重启
+async fn reconnect_detached(app: &AppHandle, instance: &ServiceInstance) -> bool {
+    let Some(pid) = pidfile::read_pid(app, &instance.name) else {
+        return false;
+    };
+    if !pidfile::is_process_alive(pid) {
+        return false;
+    }
+    
+    let app_state = app.state::<AppState>();
+    let readiness_path = lock(&app_state.readiness_path).clone();
+    let client = app_state.jarvis_http(instance);
+    if !is_ready(&client, &instance.base_url(), &readiness_path, DEFAULT_STATUS_TIMEOUT).await {
+        return false;
+    }
+    
+    *lock(&instance.detached_pid) = Some(pid);
+    instance.set_running(true);
+    let message = format!("已重新连接独立运行的JARVIS进程(PID {})", pid);
+    emit_lifecycle(app, &instance.name, JarvisLifecycle::Started, Some(message));
+    true
+}
+
+// 清理应用异常退出后遗留的JARVIS进程
+// 仅当PID文件中的进程仍存活且端口上确实是JARVIS服务时才结束该进程，避免误杀复用了该PID的其他进程
+fn cleanup_orphan_process(app: &AppHandle, instance: &ServiceInstance) -> Result<String, JarvisError> {
+    let Some(pid) = pidfile::read_pid(app, &instance.name) else {
+        return Ok("没有遗留的JARVIS进程".to_string());
     };
     
-    let requirements_path = std::env::current_dir()
-        .map_err(|e| format!("获取当前目录失败: {}", e))?
-        .parent()
-        .ok_or("无法找到父目录")?
-        .join("requirements.txt");
+    if instance.child_id() == Some(pid) {
+        return Ok("没有遗留的JARVIS进程".to_string());
+    }
+    if instance.adopted_pid() == Some(pid) {
+        return Ok("PID文件中的进程为已重新连接的独立进程".to_string());
+    }
     
-    if !requirements_path.exists() {
-        return Err("requirements.txt文件不存在".to_string());
+    if !pidfile::is_process_alive(pid) {
+        pidfile::remove_pid(app, &instance.name);
+        return Ok("已清除过期的PID文件".to_string());
     }
     
-    let output = Command::new(python_executable)
-        .args(&["-m", "pip", "install", "-r"])
-        .arg(&requirements_path)
-        .current_dir(
-            std::env::current_dir()
-                .unwrap()
-                .parent()
-                .unwrap()
-        )
-        .output()
-        .map_err(|e| format!("执行pip install失败: {}", e))?;
-    
-    if output.status.success() {
-        Ok("Python依赖安装成功".to_string())
+    let readiness_path = lock(&app.state::<AppState>().readiness_path).clone();
+    if !matches!(probe_port(instance.local_addr(), &readiness_path), PortOccupant::Jarvis) {
+        pidfile::remove_pid(app, &instance.name);
+        return Ok(format!("PID {}已被其他进程使用，仅清除PID文件", pid));
+    }
+    
+    pidfile::kill_pid(pid, pidfile::ORPHAN_KILL_GRACE).map_err(JarvisError::ProcessControlFailed)?;
+    pidfile::remove_pid(app, &instance.name);
+    Ok(format!("已结束遗留的JARVIS进程(PID {})", pid))
+}
+
+// 清理实例遗留的JARVIS进程
+#[tauri::command]
+async fn cleanup_orphans(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.find_instance(&instance_name(instance)?)?;
+    run_blocking(move || cleanup_orphan_process(&app, &instance)).await
+}
+
+// 将当前设置保存到配置文件
+#[tauri::command]
+async fn save_config(app: AppHandle, app_state: tauri::State<'_, AppState>) -> Result<String, JarvisError> {
+    let config = config::JarvisConfig::from_state(&app_state);
+    let path = config::write_config(&app, &config).map_err(JarvisError::Io)?;
+    Ok(format!("配置已保存到{}", path.display()))
+}
+
+// 设置应用启动时是否自动启动JARVIS服务，立即写入配置文件
+// 只影响应用启动时的行为，不影响手动调用start_jarvis_service
+#[tauri::command]
+async fn set_auto_start(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<String, JarvisError> {
+    config::update(&app, |config| config.auto_start = enabled).map_err(JarvisError::Io)?;
+    *lock(&app_state.auto_start) = enabled;
+    
+    if enabled {
+        Ok("已开启自动启动".to_string())
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Python依赖安装失败: {}", stderr))
+        Ok("已关闭自动启动".to_string())
+    }
+}
+
+// 设置是否以独立模式启动核心，立即写入配置文件，从下一次启动核心起生效
+// 独立模式下关闭应用不会停止核心，下次打开应用时重新连接；核心输出写入日志目录下的单独文件
+#[tauri::command]
+async fn set_detached_mode(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<String, JarvisError> {
+    config::update(&app, |config| config.detached = enabled).map_err(JarvisError::Io)?;
+    *lock(&app_state.detached) = enabled;
+    
+    if enabled {
+        Ok("已开启独立模式，下次启动JARVIS服务时生效".to_string())
+    } else {
+        Ok("已关闭独立模式，下次启动JARVIS服务时生效".to_string())
+    }
+}
+
+// 应用外壳自身的版本及运行环境信息，用于问题反馈
+#[derive(Serialize)]
+struct AppInfo {
+    version: &'static str,
+    tauri_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    backend_mode: BackendMode,
+}
+
+// 获取应用版本、Tauri版本、操作系统/架构及默认实例的后端模式
+#[tauri::command]
+fn get_app_info(app_state: tauri::State<'_, AppState>) -> AppInfo {
+    let backend_mode = if app_state.default_instance().is_remote() {
+        BackendMode::Remote
+    } else {
+        BackendMode::Local
+    };
+    
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        tauri_version: tauri::VERSION,
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        backend_mode,
+    }
+}
+
+// 导出支持包(zip)：最近的日志、诊断结果、当前配置、最近一次退出和错误信息以及应用信息
+// dest为已存在的目录时在其中生成jarvis-support-<时间戳>.zip，否则视为目标文件路径
+// 所有内容中的自定义环境变量值和访问令牌都会被替换为******，返回生成的文件路径
+#[tauri::command]
+async fn export_support_bundle(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    dest: String,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let dest = PathBuf::from(dest.trim());
+    if dest.as_os_str().is_empty() {
+        return Err(JarvisError::InvalidArgument("请指定支持包的保存位置".to_string()));
     }
+    let target = if dest.is_dir() {
+        dest.join(format!("jarvis-support-{}.zip", logs::unix_millis()))
+    } else {
+        dest
+    };
+    
+    let name = instance_name(instance)?;
+    let target_instance = app_state.find_instance(&name)?;
+    let diagnostics = run_diagnostics(app.clone(), app_state.clone(), Some(name.clone())).await?;
+    let logs = logs::recent_logs(&lock(&app_state.log_buffer), logs::LOG_BUFFER_CAPACITY);
+    let config = config::JarvisConfig::from_state(&app_state);
+    let status = serde_json::json!({
+        "instance": name,
+        "last_exit": lock(&target_instance.last_exit).clone(),
+        "last_error": lock(&app_state.last_error).clone(),
+        "start_timings": lock(&app_state.start_timings).stats(&name),
+    });
+    let app_info = get_app_info(app_state.clone());
+    
+    let logs = logs
+        .iter()
+        .filter_map(|entry| serde_json::to_string(entry).ok())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let config = toml::to_string_pretty(&config)
+        .map_err(|e| JarvisError::Internal(format!("序列化配置失败: {}", e)))?;
+    let contents = [
+        ("logs.jsonl", logs),
+        ("diagnostics.json", bundle::to_json(&diagnostics)?),
+        ("config.toml", config),
+        ("status.json", bundle::to_json(&status)?),
+        ("app_info.json", bundle::to_json(&app_info)?),
+    ];
+    let entries: Vec<bundle::BundleEntry> = contents
+        .iter()
+        .map(|(file, content)| bundle::BundleEntry::new(file, redact_env_values(&app, content)))
+        .collect();
+    
+    let path = target.clone();
+    run_blocking(move || bundle::write_zip(&path, &entries).map_err(JarvisError::Io)).await?;
+    Ok(target.display().to_string())
 }
 
-// 原有的greet命令保留用于测试
+// 原有的greet命令保留用于测试，获取应用信息请使用get_app_info
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! JARVIS is ready to serve you!", name)
@@ -197,26 +3270,602 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             greet,
+            get_app_info,
             check_jarvis_status,
+            probe_url,
+            list_models,
+            test_chat_request,
+            get_core_config,
+            update_core_config,
+            open_jarvis_socket,
+            send_jarvis_message,
+            close_jarvis_socket,
+            start_sse_stream,
+            cancel_sse_stream,
             start_jarvis_service,
+            validate_start,
             stop_jarvis_service,
+            force_kill_jarvis,
+            restart_jarvis_service,
+            restart_if_unhealthy,
+            reload_jarvis,
+            set_reload_mechanism,
             get_jarvis_running_status,
-            install_python_dependencies
+            list_instances,
+            get_jarvis_pid,
+            get_jarvis_resources,
+            get_recent_logs,
+            set_log_filter,
+            open_log_dir,
+            get_restart_count,
+            get_last_exit_status,
+            get_last_error,
+            get_timing_metrics,
+            settings::set_status_poll_interval,
+            settings::set_stall_threshold,
+            settings::set_max_concurrent_requests,
+            seconds_since_last_healthy,
+            settings::pause_monitoring,
+            settings::resume_monitoring,
+            settings::set_shutdown_grace,
+            settings::set_restart_policy,
+            settings::set_startup_timing,
+            settings::set_jarvis_env,
+            settings::unset_jarvis_env,
+            settings::set_core_args,
+            settings::set_launch_command,
+            settings::set_jarvis_port,
+            settings::set_bind_address,
+            settings::set_auth_token,
+            settings::set_transport,
+            settings::set_health_paths,
+            set_backend_mode,
+            settings::set_python_path,
+            settings::set_core_script_path,
+            settings::compute_core_hash,
+            settings::set_expected_core_hash,
+            settings::check_python_version,
+            create_venv,
+            settings::set_venv_path,
+            install_python_dependencies,
+            cancel_dependency_install,
+            upgrade_package,
+            set_dependency_install_timeout,
+            check_dependencies,
+            run_diagnostics,
+            who_has_port,
+            export_support_bundle,
+            cleanup_orphans,
+            save_config,
+            set_auto_start,
+            set_detached_mode
         ])
+        .on_window_event(|window, event| {
+            // 关闭窗口时先停止JARVIS核心进程，再允许窗口关闭，避免遗留进程
+            // 独立运行的进程不停止，供下次启动时重新连接；代价是窗口关闭后核心仍占用端口和内存，
+            // 需要通过stop_jarvis_service或下次启动后停止
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let app = window.app_handle().clone();
+                let instances: Vec<Arc<ServiceInstance>> = app
+                    .state::<AppState>()
+                    .all_instances()
+                    .into_iter()
+                    .filter(|instance| instance.has_process() && !instance.is_detached())
+                    .collect();
+                if instances.is_empty() {
+                    return;
+                }
+                
+                api.prevent_close();
+                let window = window.clone();
+                let grace = app.state::<AppState>().shutdown_grace();
+                thread::spawn(move || {
+                    for instance in instances {
+                        if let Err(e) = stop_service(&app, &instance, grace) {
+                            let message = format!("关闭窗口时停止JARVIS实例失败: {}", e);
+                            app.state::<AppState>().log_app(&instance.name, message);
+                        }
+                    }
+                    let _ = window.destroy();
+                });
+            }
+        })
         .setup(|app| {
-            // 应用启动时自动尝试启动JARVIS服务
+            // 先打开日志文件，之后的启动过程也会写入日志；失败时日志只保存在内存缓冲区中
+            let app_state = app.state::<AppState>();
+            match app.path().app_log_dir() {
+                Ok(dir) => match logs::RotatingLog::open(&dir) {
+                    Ok(log_file) => *lock(&app_state.log_file) = Some(log_file),
+                    Err(e) => {
+                        app_state.log_app(DEFAULT_INSTANCE, format!("打开日志文件失败: {}", e))
+                    }
+                },
+                Err(e) => {
+                    app_state.log_app(DEFAULT_INSTANCE, format!("获取应用日志目录失败: {}", e))
+                }
+            }
+            
+            // 读取配置文件，失败时使用默认设置
+            match config::load_config(app.handle()) {
+                Ok(config) => config.apply_to(&app_state),
+                Err(e) => app_state.log_app(DEFAULT_INSTANCE, format!("{}，使用默认配置", e)),
+            }
+            
+            // JARVIS_NO_AUTOSTART=1时不自动启动服务(用于CI及界面测试)，优先于配置文件中的设置
+            let suppressed = std::env::var("JARVIS_NO_AUTOSTART").is_ok_and(|value| value == "1");
+            if suppressed {
+                let message = "已设置JARVIS_NO_AUTOSTART，跳过自动启动JARVIS服务";
+                app_state.log_app(DEFAULT_INSTANCE, message);
+            }
+            let auto_start = !suppressed
+                && *lock(&app.state::<AppState>().auto_start)
+                && config::load_run_intent(app.handle());
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                // 延迟2秒启动，确保窗口已加载
-                tokio::time::sleep(Duration::from_secs(2)).await;
+                // 独立模式下优先重新连接上次启动且仍在运行的核心
+                let instance = app_handle.state::<AppState>().default_instance();
+                let detached = *lock(&app_handle.state::<AppState>().detached);
+                if detached
+                    && !instance.is_remote()
+                    && reconnect_detached(&app_handle, &instance).await
+                {
+                    return;
+                }
+                
+                // 先清理上次异常退出遗留的进程，避免其占用端口
+                let cleanup_app = app_handle.clone();
+                if let Err(e) = run_blocking(move || cleanup_orphan_process(&cleanup_app, &instance)).await {
+                    let message = format!("清理遗留JARVIS进程失败: {}", e);
+                    app_handle.state::<AppState>().log_app(DEFAULT_INSTANCE, message);
+                }
+                
+                // 开启了自动启动，且上次退出时服务处于运行状态(或首次启动)时自动启动JARVIS服务
+                if !auto_start {
+                    return;
+                }
                 
-                // 尝试启动JARVIS服务
-                let app_state = app_handle.state::<AppState>();
-                let _ = start_jarvis_service(app_state).await;
+                auto_start_service(app_handle).await;
             });
             
+            // 启动后台状态检查
+            spawn_status_poller(app.handle().clone());
+            
             Ok(())
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+    
+    // 测试用的HTTP服务，统计建立的连接数和处理的请求数
+    struct MockServer {
+        base_url: String,
+        connections: Arc<AtomicUsize>,
+        requests: Arc<AtomicUsize>,
+    }
+    
+    impl MockServer {
+        // 在随机端口上启动，respond根据请求序号(从0开始)返回状态码，同一连接上可处理多个请求
+        async fn start(respond: impl Fn(usize) -> u16 + Send + Sync + 'static) -> Self {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            Self::serve(listener, respond)
+        }
+        
+        fn serve(
+            listener: tokio::net::TcpListener,
+            respond: impl Fn(usize) -> u16 + Send + Sync + 'static,
+        ) -> Self {
+            let server = Self {
+                base_url: format!("http://{}", listener.local_addr().unwrap()),
+                connections: Arc::new(AtomicUsize::new(0)),
+                requests: Arc::new(AtomicUsize::new(0)),
+            };
+            let connections = server.connections.clone();
+            let requests = server.requests.clone();
+            let respond = Arc::new(respond);
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    connections.fetch_add(1, Ordering::SeqCst);
+                    let requests = requests.clone();
+                    let respond = respond.clone();
+                    tokio::spawn(async move {
+                        let mut stream = tokio::io::BufReader::new(stream);
+                        loop {
+                            // 测试请求均不带请求体，读到空行即为一个完整的请求
+                            let mut line = String::new();
+                            loop {
+                                line.clear();
+                                match stream.read_line(&mut line).await {
+                                    Ok(0) | Err(_) => return,
+                                    Ok(_) if line == "\r\n" => break,
+                                    Ok(_) => {}
+                                }
+                            }
+                            let status = respond(requests.fetch_add(1, Ordering::SeqCst));
+                            let body = r#"{"status":"ok"}"#;
+                            let response = format!(
+                                "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\n\
+                                 Content-Length: {}\r\n\r\n{}",
+                                status,
+                                body.len(),
+                                body
+                            );
+                            if stream.get_mut().write_all(response.as_bytes()).await.is_err() {
+                                return;
+                            }
+                        }
+                    });
+                }
+            });
+            server
+        }
+        
+        fn connections(&self) -> usize {
+            self.connections.load(Ordering::SeqCst)
+        }
+        
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+    
+    // 持有锁的线程panic后锁被污染，之后的命令仍能正常读取和修改状态
+    #[test]
+    fn poisoned_process_lock_is_recovered() {
+        let instance = Arc::new(ServiceInstance::new("test"));
+        let poisoner = {
+            let instance = instance.clone();
+            thread::spawn(move || {
+                let _guard = instance.python_process.lock().unwrap();
+                panic!("模拟持有进程锁时panic");
+            })
+        };
+        assert!(poisoner.join().is_err());
+        assert!(instance.python_process.is_poisoned());
+        
+        assert_eq!(instance.pid(), None);
+        assert!(!instance.is_running());
+        assert!(instance.take_process().is_none());
+        instance.set_running(true);
+        assert!(instance.is_running());
+    }
+    
+    // 一个实例正在启动或停止(持有process_op)时，其他命令查询状态不需要等待该操作完成
+    #[tokio::test]
+    async fn slow_process_operation_does_not_block_status_queries() {
+        let instance = Arc::new(ServiceInstance::new("test"));
+        let (held_tx, held_rx) = tokio::sync::oneshot::channel();
+        let slow = {
+            let instance = instance.clone();
+            run_blocking(move || {
+                let _operation = lock(&instance.process_op);
+                let _ = held_tx.send(());
+                thread::sleep(Duration::from_millis(500));
+                Ok(())
+            })
+        };
+        let query = async {
+            held_rx.await.expect("慢操作未开始");
+            let started = Instant::now();
+            let state = (instance.pid(), instance.has_process(), instance.is_running());
+            (started.elapsed(), state)
+        };
+        
+        let (slow, (elapsed, state)) = tokio::join!(slow, query);
+        slow.expect("慢操作失败");
+        assert_eq!(state, (None, false, false));
+        assert!(elapsed < Duration::from_millis(100), "状态查询等待了{:?}", elapsed);
+    }
+    
+    // Unix socket传输时普通请求、重试逻辑和鉴权头与TCP相同，均经由实例的HTTP客户端发送
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn requests_use_unix_socket_transport() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        let dir = std::env::temp_dir().join(format!("jarvis-uds-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("core.sock");
+        let _ = std::fs::remove_file(&socket);
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let read = stream.read(&mut request).await.unwrap();
+            let body = r#"{"status":"ok"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+        
+        let instance = ServiceInstance::new("test");
+        *lock(&instance.unix_socket) = Some(socket.clone());
+        let http = JarvisHttp {
+            client: instance.http_client(&build_http_client()),
+            auth_token: Some("secret-token".to_string()),
+        };
+        let (status, body) =
+            get_jarvis(&http, &instance.base_url(), "/status", DEFAULT_STATUS_TIMEOUT, 2)
+                .await
+                .expect("通过Unix socket请求失败");
+        
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body, br#"{"status":"ok"}"#);
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET /status HTTP/1.1"));
+        assert!(request.contains("authorization: Bearer secret-token"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    
+    // 多次状态检查复用连接池中的连接，不会每次都重新建立TCP连接
+    #[tokio::test]
+    async fn repeated_status_checks_reuse_connection() {
+        let server = MockServer::start(|_| 200).await;
+        let app_state = AppState::default();
+        let instance = app_state.default_instance();
+        
+        for _ in 0..5 {
+            let http = app_state.jarvis_http(&instance);
+            let path = DEFAULT_HEALTH_PATH;
+            fetch_jarvis_status(&http, &server.base_url, path, DEFAULT_STATUS_TIMEOUT, 0)
+                .await
+                .expect("状态检查失败");
+        }
+        
+        assert_eq!(server.requests(), 5);
+        assert_eq!(server.connections(), 1);
+    }
+    
+    // 服务启动后一段时间才就绪：就绪前返回503，就绪后轮询应很快成功
+    #[tokio::test]
+    async fn wait_until_ready_succeeds_once_service_becomes_ready() {
+        let ready_at = Instant::now() + Duration::from_millis(500);
+        let server =
+            MockServer::start(move |_| if Instant::now() >= ready_at { 200 } else { 503 }).await;
+        let app_state = AppState::default();
+        let http = app_state.jarvis_http(&app_state.default_instance());
+        
+        let started = Instant::now();
+        wait_until_ready(&http, &server.base_url, DEFAULT_HEALTH_PATH, Duration::from_secs(10))
+            .await
+            .expect("服务就绪后应等待成功");
+        
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(500));
+        assert!(elapsed < Duration::from_millis(500) + MAX_READY_POLL_DELAY);
+        assert!(server.requests() > 1);
+    }
+    
+    // 端口尚未监听(连接被拒绝)时继续轮询，监听后即可就绪
+    #[tokio::test]
+    async fn wait_until_ready_retries_until_port_is_listening() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let app_state = AppState::default();
+        let http = app_state.jarvis_http(&app_state.default_instance());
+        
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+            let server = MockServer::serve(listener, |_| 200);
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            drop(server);
+        });
+        
+        let base_url = format!("http://{}", addr);
+        wait_until_ready(&http, &base_url, DEFAULT_HEALTH_PATH, Duration::from_secs(10))
+            .await
+            .expect("端口开始监听后应等待成功");
+    }
+    
+    // 一直未就绪时在超时前返回Timeout，且轮询间隔按指数退避，不会频繁请求
+    #[tokio::test]
+    async fn wait_until_ready_times_out_with_backoff() {
+        let server = MockServer::start(|_| 503).await;
+        let app_state = AppState::default();
+        let http = app_state.jarvis_http(&app_state.default_instance());
+        let timeout = Duration::from_millis(1500);
+        
+        let started = Instant::now();
+        let result = wait_until_ready(&http, &server.base_url, DEFAULT_HEALTH_PATH, timeout).await;
+        
+        assert!(matches!(result, Err(JarvisError::Timeout(_))));
+        assert!(started.elapsed() <= timeout);
+        // 200ms起步逐次翻倍：0ms、200ms、600ms、1400ms各请求一次
+        assert!((3..=4).contains(&server.requests()));
+    }
+    
+    // 取一个当前空闲的本地端口，稍后才开始监听
+    fn unused_local_addr() -> std::net::SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    }
+    
+    // 服务约200ms后才开始监听，之前的连接被拒绝会按退避重试，监听后请求成功
+    #[tokio::test]
+    async fn get_jarvis_retries_until_service_comes_online() {
+        let addr = unused_local_addr();
+        let app_state = AppState::default();
+        let http = app_state.jarvis_http(&app_state.default_instance());
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+            let _ = ready_tx.send(MockServer::serve(listener, |_| 200));
+            std::future::pending::<()>().await;
+        });
+        
+        let base_url = format!("http://{}", addr);
+        let path = DEFAULT_HEALTH_PATH;
+        let (status, _) = get_jarvis(&http, &base_url, path, DEFAULT_STATUS_TIMEOUT, 5)
+            .await
+            .expect("服务上线后应请求成功");
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(ready_rx.await.unwrap().requests(), 1);
+    }
+    
+    // HTTP错误状态码直接返回，只请求一次，不重试
+    #[tokio::test]
+    async fn get_jarvis_does_not_retry_http_errors() {
+        let server = MockServer::start(|_| 500).await;
+        let app_state = AppState::default();
+        let http = app_state.jarvis_http(&app_state.default_instance());
+        
+        let (status, body) =
+            get_jarvis(&http, &server.base_url, DEFAULT_HEALTH_PATH, DEFAULT_STATUS_TIMEOUT, 3)
+                .await
+                .unwrap();
+        assert_eq!(status, reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body, br#"{"status":"ok"}"#);
+        assert_eq!(server.requests(), 1);
+    }
+    
+    // 重试次数用完后返回连接错误
+    #[tokio::test]
+    async fn get_with_retry_returns_connect_error_after_retries() {
+        let url = format!("http://{}{}", unused_local_addr(), DEFAULT_HEALTH_PATH);
+        let app_state = AppState::default();
+        let http = app_state.jarvis_http(&app_state.default_instance());
+        
+        let started = Instant::now();
+        let error = get_with_retry(&http, &url, DEFAULT_STATUS_TIMEOUT, 2).await.unwrap_err();
+        assert!(error.is_connect(), "{}", error);
+        // 两次重试分别等待200ms和400ms
+        assert!(started.elapsed() >= INITIAL_RETRY_BACKOFF * 3);
+        
+        let base_url = url.trim_end_matches(DEFAULT_HEALTH_PATH).to_string();
+        let path = DEFAULT_HEALTH_PATH;
+        let result = get_jarvis(&http, &base_url, path, DEFAULT_STATUS_TIMEOUT, 0).await;
+        assert!(matches!(result, Err(JarvisError::HttpError(_))));
+    }
+    
+    // 配置的脚本路径没有上级目录(根目录或不带目录的文件名)时返回错误而不是panic
+    #[test]
+    fn configured_script_without_parent_is_rejected() {
+        for script in ["/", CORE_SCRIPT_NAME] {
+            let result = configured_script_dir(Path::new(script));
+            assert!(matches!(result, Err(JarvisError::ScriptMissing(_))), "{}", script);
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join(CORE_SCRIPT_NAME);
+        assert_eq!(configured_script_dir(&script).unwrap(), dir.path());
+    }
+    
+    // 候选目录不存在时跳过，使用下一个包含核心脚本的目录，工作目录为其上级目录
+    #[test]
+    fn core_script_lookup_skips_missing_directories() {
+        let root = tempfile::tempdir().unwrap();
+        let core_dir = root.path().join(CORE_DIR_NAME);
+        std::fs::create_dir_all(&core_dir).unwrap();
+        std::fs::write(core_dir.join(CORE_SCRIPT_NAME), "").unwrap();
+        let missing = root.path().join("不存在的目录").join(CORE_DIR_NAME);
+        
+        let candidates = vec![("资源目录", missing.clone()), ("JARVIS_CORE_DIR", core_dir.clone())];
+        let (strategy, script, working_dir) = find_core_script(&candidates).unwrap();
+        assert_eq!(strategy, "JARVIS_CORE_DIR");
+        assert_eq!(script, core_dir.join(CORE_SCRIPT_NAME));
+        assert_eq!(working_dir, root.path());
+        
+        let result = find_core_script(&[("资源目录", missing)]);
+        let tried = matches!(result, Err(JarvisError::ScriptMissing(m)) if m.contains("资源目录"));
+        assert!(tried);
+    }
+    
+    // 所有候选目录都不存在时requirements.txt查找返回错误
+    #[test]
+    fn requirements_lookup_reports_missing_directories() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join(REQUIREMENTS_FILE_NAME), "requests\n").unwrap();
+        let missing = root.path().join("不存在的目录");
+        
+        let (path, dir) = find_requirements(&[missing.clone(), root.path().to_path_buf()]).unwrap();
+        assert_eq!(path, root.path().join(REQUIREMENTS_FILE_NAME));
+        assert_eq!(dir, root.path());
+        
+        let result = find_requirements(&[missing]);
+        assert!(matches!(result, Err(JarvisError::RequirementsMissing(_))));
+        assert_eq!(parent_dir(Path::new("/")), None);
+    }
+    
+    // 其他命令执行期间同时到达的两个启动请求合并为一次启动，并按最强的要求等待就绪
+    #[tokio::test]
+    async fn queued_starts_are_coalesced_into_one_spawn() {
+        let queue = Arc::new(LifecycleQueue::default());
+        let spawns = Arc::new(Mutex::new(Vec::new()));
+        let (release, blocked) = tokio::sync::oneshot::channel::<()>();
+        let restart = tokio::spawn({
+            let queue = queue.clone();
+            async move {
+                let restart = |_| async move {
+                    blocked.await.ok();
+                    Ok("重启成功".to_string())
+                };
+                queue.run(LifecycleIntent::Restart, restart).await
+            }
+        });
+        // 等重启开始执行(占用命令队列)后再发起启动请求
+        while queue.applied.try_lock().is_ok() {
+            tokio::task::yield_now().await;
+        }
+        
+        let start = |wait_for_ready| {
+            let queue = queue.clone();
+            let spawns = spawns.clone();
+            tokio::spawn(async move {
+                let spawn = move |target| async move {
+                    lock(&spawns).push(target);
+                    Ok("JARVIS服务启动成功".to_string())
+                };
+                queue.run(LifecycleIntent::Start { wait_for_ready }, spawn).await
+            })
+        };
+        let first = start(true);
+        let second = start(false);
+        while lock(&queue.desired).seq < 3 {
+            tokio::task::yield_now().await;
+        }
+        release.send(()).unwrap();
+        
+        assert_eq!(restart.await.unwrap().unwrap(), "重启成功");
+        assert_eq!(first.await.unwrap().unwrap(), "JARVIS服务启动成功");
+        assert_eq!(second.await.unwrap().unwrap(), "JARVIS服务启动成功");
+        assert_eq!(*lock(&spawns), vec![LifecycleIntent::Start { wait_for_ready: true }]);
+        
+        // 等待要求只作用于合并的那一批请求，之后的启动不会继承
+        start(false).await.unwrap().unwrap();
+        assert_eq!(lock(&spawns).last(), Some(&LifecycleIntent::Start { wait_for_ready: false }));
+    }
+    
+    // 启动后从未成功过健康检查的实例超过阈值同样判定为卡死，成功一次后重新计时
+    #[test]
+    fn stall_is_measured_from_start_until_first_healthy_check() {
+        let instance = ServiceInstance::new("work");
+        let ago = |secs| Instant::now().checked_sub(Duration::from_secs(secs)).unwrap();
+        assert_eq!(instance.stalled_seconds(5), None);
+        
+        instance.set_running(true);
+        assert_eq!(instance.stalled_seconds(5), None);
+        *lock(&instance.health_window_start) = Some(ago(10));
+        assert_eq!(instance.stalled_seconds(5), Some(10));
+        assert_eq!(instance.stalled_seconds(30), None);
+        
+        instance.mark_healthy();
+        assert_eq!(instance.stalled_seconds(5), None);
+        *lock(&instance.last_successful_health) = Some(ago(8));
+        assert_eq!(instance.stalled_seconds(5), Some(8));
+        
+        // 停止后不再报告
+        instance.set_running(false);
+        assert_eq!(instance.stalled_seconds(5), None);
+    }
+}
@@ -0,0 +1,88 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::JarvisError;
+use crate::logs::unix_millis;
+use crate::{lock, AppState};
+
+// 服务生命周期状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JarvisLifecycle {
+    Starting,
+    Started,
+    Stopping,
+    Stopped,
+    // 进程意外退出
+    Crashed,
+    // 启动或停止操作失败，reason中为失败原因
+    Failed,
+}
+
+// jarvis-lifecycle事件内容
+#[derive(Clone, Serialize)]
+struct LifecycleEvent<'a> {
+    instance: &'a str,
+    state: JarvisLifecycle,
+    timestamp_ms: u64,
+    reason: Option<String>,
+}
+
+// 最近一次生命周期错误，供前端在加载完成后查询启动早期错过的事件
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorInfo {
+    pub instance: String,
+    pub state: JarvisLifecycle,
+    // 来自JarvisError时为其错误码
+    pub code: Option<String>,
+    pub message: String,
+    pub timestamp_ms: u64,
+}
+
+// 记录失败原因，覆盖之前的错误
+pub fn record_error(app: &AppHandle, instance: &str, error: &JarvisError) {
+    store_error(app, instance, JarvisLifecycle::Failed, Some(error.code()), error.message());
+}
+
+fn store_error(
+    app: &AppHandle,
+    instance: &str,
+    state: JarvisLifecycle,
+    code: Option<&str>,
+    message: &str,
+) {
+    let info = ErrorInfo {
+        instance: instance.to_string(),
+        state,
+        code: code.map(str::to_string),
+        message: message.to_string(),
+        timestamp_ms: unix_millis(),
+    };
+    *lock(&app.state::<AppState>().last_error) = Some(info);
+}
+
+// 通知前端实例启动/停止失败或进程崩溃，同时连同错误码记录为最近一次错误
+pub fn emit_failure(app: &AppHandle, instance: &str, state: JarvisLifecycle, error: &JarvisError) {
+    store_error(app, instance, state, Some(error.code()), error.message());
+    emit_lifecycle(app, instance, state, Some(error.to_string()));
+}
+
+// 通知前端实例进入新的生命周期状态，实例启动成功后清除该实例的错误
+// 失败和崩溃使用emit_failure，以便记录错误码
+pub fn emit_lifecycle(app: &AppHandle, instance: &str, state: JarvisLifecycle, reason: Option<String>) {
+    if state == JarvisLifecycle::Started {
+        let app_state = app.state::<AppState>();
+        let mut last_error = lock(&app_state.last_error);
+        if last_error.as_ref().is_some_and(|info| info.instance == instance) {
+            *last_error = None;
+        }
+    }
+    
+    let event = LifecycleEvent {
+        instance,
+        state,
+        timestamp_ms: unix_millis(),
+        reason,
+    };
+    let _ = app.emit("jarvis-lifecycle", event);
+}
@@ -0,0 +1,281 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+
+// 日志缓冲区最多保留的行数
+pub const LOG_BUFFER_CAPACITY: usize = 1000;
+
+// 日志来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    // 应用自身的日志(启动过程、配置问题等)
+    App,
+}
+
+impl LogStream {
+    // 转发该来源日志时使用的前端事件名
+    pub fn event_name(self) -> &'static str {
+        match self {
+            LogStream::Stdout => "jarvis-stdout",
+            LogStream::Stderr => "jarvis-stderr",
+            LogStream::App => "jarvis-app-log",
+        }
+    }
+}
+
+// 日志文件名，轮转后的旧文件依次为jarvis.log.1、jarvis.log.2……
+pub const LOG_FILE_NAME: &str = "jarvis.log";
+
+// 单个日志文件的大小上限及保留的文件数(含当前文件)
+const LOG_FILE_MAX_BYTES: u64 = 1024 * 1024;
+const LOG_FILE_COUNT: usize = 5;
+
+// 默认只向前端实时推送WARNING及以上级别的日志
+pub const DEFAULT_LOG_LEVEL: &str = "WARNING";
+
+// Python logging的日志级别，按严重程度排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl LogLevel {
+    // 解析级别名称，不区分大小写
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARNING" | "WARN" => Some(LogLevel::Warning),
+            "ERROR" => Some(LogLevel::Error),
+            "CRITICAL" | "FATAL" => Some(LogLevel::Critical),
+            _ => None,
+        }
+    }
+    
+    // 识别Python logging默认格式的级别前缀，例如"WARNING:root:..."
+    pub fn from_line(line: &str) -> Option<Self> {
+        let (prefix, _) = line.split_once(':')?;
+        match prefix {
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARNING" => Some(LogLevel::Warning),
+            "ERROR" => Some(LogLevel::Error),
+            "CRITICAL" => Some(LogLevel::Critical),
+            _ => None,
+        }
+    }
+}
+
+// 判断一行日志是否需要实时推送，无法识别级别的行始终推送，避免被静默丢弃
+pub fn passes_filter(line: &str, min_level: LogLevel) -> bool {
+    LogLevel::from_line(line).is_none_or(|level| level >= min_level)
+}
+
+// 一条捕获的日志
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp_ms: u64,
+    pub instance: String,
+    pub stream: LogStream,
+    pub line: String,
+}
+
+impl LogEntry {
+    pub fn new(instance: &str, stream: LogStream, line: String) -> Self {
+        Self {
+            timestamp_ms: unix_millis(),
+            instance: instance.to_string(),
+            stream,
+            line,
+        }
+    }
+}
+
+// 当前时间的Unix毫秒时间戳
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// 写入日志缓冲区，超出容量时丢弃最旧的日志
+pub fn push_log(buffer: &mut VecDeque<LogEntry>, entry: LogEntry) {
+    if buffer.len() >= LOG_BUFFER_CAPACITY {
+        buffer.pop_front();
+    }
+    buffer.push_back(entry);
+}
+
+// 获取最近的若干条日志，按时间先后排列
+pub fn recent_logs(buffer: &VecDeque<LogEntry>, lines: usize) -> Vec<LogEntry> {
+    let skip = buffer.len().saturating_sub(lines);
+    buffer.iter().skip(skip).cloned().collect()
+}
+
+// 按大小轮转的日志文件
+pub struct RotatingLog {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingLog {
+    // 在指定目录下打开(或创建)日志文件，追加写入
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(LOG_FILE_NAME);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+    
+    // 当前正在写入的日志文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    // 写入一条日志，超出大小上限时先轮转
+    pub fn write(&mut self, entry: &LogEntry) -> io::Result<()> {
+        let stream = match entry.stream {
+            LogStream::Stdout => "stdout",
+            LogStream::Stderr => "stderr",
+            LogStream::App => "app",
+        };
+        let line = format!("{} [{}] [{}] {}\n", entry.timestamp_ms, entry.instance, stream, entry.line);
+        
+        if self.size > 0 && self.size + line.len() as u64 > LOG_FILE_MAX_BYTES {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+    
+    // 依次将jarvis.log.N重命名为jarvis.log.N+1，最旧的文件被覆盖
+    fn rotate(&mut self) -> io::Result<()> {
+        for index in (1..LOG_FILE_COUNT - 1).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, index + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn entry(line: &str) -> LogEntry {
+        LogEntry {
+            timestamp_ms: 0,
+            instance: "默认".to_string(),
+            stream: LogStream::App,
+            line: line.to_string(),
+        }
+    }
+    
+    // 一条日志写入文件后占用的字节数，与RotatingLog::write的格式保持一致
+    fn entry_len(line: &str) -> u64 {
+        format!("0 [默认] [app] {}\n", line).len() as u64
+    }
+    
+    // 缓冲区满后丢弃最旧的日志，多字节内容原样保留
+    #[test]
+    fn buffer_drops_oldest_entries() {
+        let mut buffer = VecDeque::new();
+        for index in 0..LOG_BUFFER_CAPACITY + 10 {
+            push_log(&mut buffer, entry(&format!("第{}行：模型加载完成", index)));
+        }
+        
+        assert_eq!(buffer.len(), LOG_BUFFER_CAPACITY);
+        assert_eq!(buffer.front().unwrap().line, "第10行：模型加载完成");
+        
+        let lines: Vec<String> = recent_logs(&buffer, 2).into_iter().map(|e| e.line).collect();
+        let last = LOG_BUFFER_CAPACITY + 9;
+        assert_eq!(
+            lines,
+            vec![format!("第{}行：模型加载完成", last - 1), format!("第{}行：模型加载完成", last)]
+        );
+        assert_eq!(recent_logs(&buffer, LOG_BUFFER_CAPACITY * 2).len(), LOG_BUFFER_CAPACITY);
+    }
+    
+    // 恰好写满上限时不轮转，再写一行才轮转，且不会把多字节字符截断在两个文件之间
+    #[test]
+    fn rotates_at_size_boundary_with_multibyte_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = RotatingLog::open(dir.path()).unwrap();
+        let line = "语音识别服务启动中，正在加载模型……".repeat(20);
+        let overhead = entry_len("");
+        let count = (LOG_FILE_MAX_BYTES - overhead) / entry_len(&line);
+        for _ in 0..count {
+            log.write(&entry(&line)).unwrap();
+        }
+        let padding = (LOG_FILE_MAX_BYTES - count * entry_len(&line) - overhead) as usize;
+        let last_line = format!("{}{}", "满".repeat(padding / 3), "a".repeat(padding % 3));
+        log.write(&entry(&last_line)).unwrap();
+        
+        let rotated = rotated_path(log.path(), 1);
+        assert_eq!(fs::metadata(log.path()).unwrap().len(), LOG_FILE_MAX_BYTES);
+        assert!(!rotated.exists());
+        
+        log.write(&entry("轮转后的第一行")).unwrap();
+        
+        let old = fs::read_to_string(&rotated).unwrap();
+        assert_eq!(old.len() as u64, LOG_FILE_MAX_BYTES);
+        assert!(old.ends_with(&format!("{}\n", last_line)));
+        assert_eq!(fs::read_to_string(log.path()).unwrap(), "0 [默认] [app] 轮转后的第一行\n");
+    }
+    
+    // 多次轮转后只保留LOG_FILE_COUNT个文件
+    #[test]
+    fn keeps_limited_number_of_rotated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut log = RotatingLog::open(dir.path()).unwrap();
+        let line = "日".repeat((LOG_FILE_MAX_BYTES / 3 / 2) as usize + 1);
+        for _ in 0..LOG_FILE_COUNT * 2 {
+            log.write(&entry(&line)).unwrap();
+        }
+        
+        for index in 1..LOG_FILE_COUNT {
+            assert!(rotated_path(log.path(), index).exists());
+        }
+        assert!(!rotated_path(log.path(), LOG_FILE_COUNT).exists());
+    }
+    
+    // 重新打开时从已有文件大小继续计算，不会超出上限
+    #[test]
+    fn reopen_continues_from_existing_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let line = "重".repeat((LOG_FILE_MAX_BYTES / 3 / 2) as usize + 1);
+        RotatingLog::open(dir.path()).unwrap().write(&entry(&line)).unwrap();
+        
+        let mut log = RotatingLog::open(dir.path()).unwrap();
+        log.write(&entry(&line)).unwrap();
+        
+        assert!(rotated_path(log.path(), 1).exists());
+        assert_eq!(fs::metadata(log.path()).unwrap().len(), entry_len(&line));
+    }
+}
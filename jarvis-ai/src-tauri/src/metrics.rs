@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+use serde::Serialize;
+
+// 保留最近多少次启动的耗时记录
+const MAX_TIMING_HISTORY: usize = 20;
+
+// 单次启动的耗时：spawn_ms为启动前检查加创建进程的耗时，ready_ms为进程创建后到首次就绪检查通过的耗时
+#[derive(Debug, Clone, Serialize)]
+pub struct StartTiming {
+    pub instance: String,
+    pub spawn_ms: u64,
+    pub ready_ms: u64,
+    pub timestamp_ms: u64,
+}
+
+// 一组耗时的最小、最大和平均值
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DurationSummary {
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub avg_ms: u64,
+}
+
+impl DurationSummary {
+    fn from_values(values: &[u64]) -> Option<Self> {
+        let min_ms = *values.iter().min()?;
+        let max_ms = *values.iter().max()?;
+        let avg_ms = values.iter().sum::<u64>() / values.len() as u64;
+        Some(Self { min_ms, max_ms, avg_ms })
+    }
+}
+
+// get_timing_metrics的返回内容，没有记录时spawn和ready为None
+#[derive(Debug, Clone, Serialize)]
+pub struct TimingStats {
+    pub count: usize,
+    pub spawn: Option<DurationSummary>,
+    pub ready: Option<DurationSummary>,
+    pub recent: Vec<StartTiming>,
+}
+
+// 最近的启动耗时记录，超出容量时丢弃最早的记录
+#[derive(Debug, Default)]
+pub struct TimingHistory {
+    entries: VecDeque<StartTiming>,
+}
+
+impl TimingHistory {
+    pub fn record(&mut self, timing: StartTiming) {
+        if self.entries.len() >= MAX_TIMING_HISTORY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(timing);
+    }
+    
+    // 统计指定实例最近的启动耗时
+    pub fn stats(&self, instance: &str) -> TimingStats {
+        let recent: Vec<StartTiming> = self
+            .entries
+            .iter()
+            .filter(|timing| timing.instance == instance)
+            .cloned()
+            .collect();
+        let spawn: Vec<u64> = recent.iter().map(|timing| timing.spawn_ms).collect();
+        let ready: Vec<u64> = recent.iter().map(|timing| timing.ready_ms).collect();
+        
+        TimingStats {
+            count: recent.len(),
+            spawn: DurationSummary::from_values(&spawn),
+            ready: DurationSummary::from_values(&ready),
+            recent,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 模型列表的缓存有效期
+pub const MODEL_CACHE_TTL: Duration = Duration::from_secs(30);
+
+// JARVIS核心/models接口返回的模型信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub context_length: Option<u32>,
+}
+
+// 某个实例最近一次获取的模型列表
+pub struct ModelCache {
+    base_url: String,
+    fetched_at: Instant,
+    models: Vec<ModelInfo>,
+}
+
+impl ModelCache {
+    pub fn new(base_url: String, models: Vec<ModelInfo>) -> Self {
+        Self {
+            base_url,
+            fetched_at: Instant::now(),
+            models,
+        }
+    }
+    
+    // 缓存未过期且服务地址未变化时返回缓存的模型列表
+    pub fn get(&self, base_url: &str) -> Option<Vec<ModelInfo>> {
+        self.get_at(base_url, Instant::now())
+    }
+    
+    fn get_at(&self, base_url: &str, now: Instant) -> Option<Vec<ModelInfo>> {
+        let age = now.saturating_duration_since(self.fetched_at);
+        (self.base_url == base_url && age < MODEL_CACHE_TTL).then(|| self.models.clone())
+    }
+}
+
+// 解析/models的返回内容，兼容直接返回数组以及{"models": [...]}、{"data": [...]}两种包装
+pub fn parse_models(value: Value) -> Result<Vec<ModelInfo>, String> {
+    let list = match value {
+        Value::Object(mut object) => object
+            .remove("models")
+            .or_else(|| object.remove("data"))
+            .ok_or_else(|| "响应中缺少models字段".to_string())?,
+        other => other,
+    };
+    serde_json::from_value(list).map_err(|e| format!("解析模型列表失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    const BASE_URL: &str = "http://127.0.0.1:8000";
+    
+    fn cache() -> ModelCache {
+        let models = parse_models(json!([{"id": "qwen"}])).unwrap();
+        ModelCache::new(BASE_URL.to_string(), models)
+    }
+    
+    #[test]
+    fn cache_expires_after_ttl() {
+        let cache = cache();
+        
+        assert!(cache.get_at(BASE_URL, cache.fetched_at).is_some());
+        let almost_expired = cache.fetched_at + MODEL_CACHE_TTL - Duration::from_millis(1);
+        assert!(cache.get_at(BASE_URL, almost_expired).is_some());
+        assert!(cache.get_at(BASE_URL, cache.fetched_at + MODEL_CACHE_TTL).is_none());
+    }
+    
+    // 服务地址变化(如切换端口或远程模式)后不再使用旧地址的缓存
+    #[test]
+    fn cache_is_invalidated_when_base_url_changes() {
+        let cache = cache();
+        
+        assert!(cache.get(BASE_URL).is_some());
+        assert!(cache.get("http://127.0.0.1:8001").is_none());
+    }
+    
+    #[test]
+    fn parse_models_accepts_bare_array() {
+        let models = parse_models(json!([
+            {"id": "qwen", "name": "通义千问", "context_length": 32768},
+            {"id": "llama"},
+        ]))
+        .unwrap();
+        
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name.as_deref(), Some("通义千问"));
+        assert_eq!(models[0].context_length, Some(32768));
+        assert_eq!(models[1].id, "llama");
+        assert_eq!(models[1].name, None);
+    }
+    
+    #[test]
+    fn parse_models_accepts_wrapped_lists() {
+        let wrapped = parse_models(json!({"models": [{"id": "qwen"}]})).unwrap();
+        let openai = parse_models(json!({"object": "list", "data": [{"id": "llama"}]})).unwrap();
+        
+        assert_eq!(wrapped[0].id, "qwen");
+        assert_eq!(openai[0].id, "llama");
+    }
+    
+    #[test]
+    fn parse_models_rejects_unexpected_shapes() {
+        assert!(parse_models(json!({"items": []})).is_err());
+        assert!(parse_models(json!([{"name": "缺少id"}])).is_err());
+    }
+}
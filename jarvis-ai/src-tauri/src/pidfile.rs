@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+// 默认实例的PID文件名，位于应用数据目录下，其他实例为jarvis-core-<实例名>.pid
+const PIDFILE_NAME: &str = "jarvis-core.pid";
+
+// 独立模式下默认实例的输出日志文件名，位于应用日志目录下
+const DETACHED_LOG_NAME: &str = "jarvis-core.out.log";
+
+// 结束遗留进程时等待其正常退出的时间
+pub const ORPHAN_KILL_GRACE: Duration = Duration::from_secs(5);
+
+// 强制结束后等待进程退出的时间
+const KILL_WAIT: Duration = Duration::from_secs(5);
+
+// 实例的PID文件路径
+pub fn pidfile_path(app: &AppHandle, instance: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+    if instance == crate::DEFAULT_INSTANCE {
+        Ok(dir.join(PIDFILE_NAME))
+    } else {
+        Ok(dir.join(format!("jarvis-core-{}.pid", instance)))
+    }
+}
+
+// 记录JARVIS进程PID
+pub fn write_pid(app: &AppHandle, instance: &str, pid: u32) -> Result<(), String> {
+    let path = pidfile_path(app, instance)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("创建数据目录{}失败: {}", dir.display(), e))?;
+    }
+    fs::write(&path, pid.to_string()).map_err(|e| format!("写入PID文件{}失败: {}", path.display(), e))
+}
+
+// 读取PID文件，文件不存在或内容无效时返回None
+pub fn read_pid(app: &AppHandle, instance: &str) -> Option<u32> {
+    let content = fs::read_to_string(pidfile_path(app, instance).ok()?).ok()?;
+    content.trim().parse().ok()
+}
+
+// 打开独立模式下核心输出的日志文件(追加写入)，位于应用日志目录下
+// 独立运行的进程在应用退出后仍会输出，不能使用管道
+pub fn open_detached_log(app: &AppHandle, instance: &str) -> Result<fs::File, String> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("获取应用日志目录失败: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("创建日志目录{}失败: {}", dir.display(), e))?;
+    let path = if instance == crate::DEFAULT_INSTANCE {
+        dir.join(DETACHED_LOG_NAME)
+    } else {
+        dir.join(format!("jarvis-core-{}.out.log", instance))
+    };
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("打开日志文件{}失败: {}", path.display(), e))
+}
+
+// 删除PID文件
+pub fn remove_pid(app: &AppHandle, instance: &str) {
+    if let Ok(path) = pidfile_path(app, instance) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// 检查进程是否存活
+#[cfg(unix)]
+pub fn is_process_alive(pid: u32) -> bool {
+    // 信号0只检查进程是否存在，EPERM表示进程存在但无权限发送信号
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+// 检查进程是否存活
+#[cfg(windows)]
+pub fn is_process_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+        .unwrap_or(false)
+}
+
+// 按PID结束进程及其子进程：先请求正常退出，等待grace后仍未退出则强制结束
+#[cfg(unix)]
+pub fn kill_pid(pid: u32, grace: Duration) -> Result<(), String> {
+    signal_group(pid, libc::SIGTERM);
+    if wait_for_pid_exit(pid, grace) {
+        return Ok(());
+    }
+    
+    signal_group(pid, libc::SIGKILL);
+    if wait_for_pid_exit(pid, KILL_WAIT) {
+        Ok(())
+    } else {
+        Err(format!("无法结束进程(PID {})", pid))
+    }
+}
+
+// 核心进程启动时总是进程组组长，向整个进程组发送信号以同时结束uvicorn的服务进程
+// 进程不是组长时(例如PID已被复用)只向该进程发送
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: libc::c_int) {
+    let pid_t = pid as libc::pid_t;
+    if unsafe { libc::kill(-pid_t, signal) } != 0 {
+        unsafe { libc::kill(pid_t, signal) };
+    }
+}
+
+// 按PID结束进程及其子进程：先请求正常退出，等待grace后仍未退出则强制结束
+#[cfg(windows)]
+pub fn kill_pid(pid: u32, grace: Duration) -> Result<(), String> {
+    let pid_arg = pid.to_string();
+    let _ = std::process::Command::new("taskkill")
+        .args(["/T", "/PID", &pid_arg])
+        .output();
+    if wait_for_pid_exit(pid, grace) {
+        return Ok(());
+    }
+    
+    let _ = std::process::Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid_arg])
+        .output();
+    if wait_for_pid_exit(pid, KILL_WAIT) {
+        Ok(())
+    } else {
+        Err(format!("无法结束进程(PID {})", pid))
+    }
+}
+
+// 等待进程退出，返回进程是否已退出
+fn wait_for_pid_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while is_process_alive(pid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    true
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+    
+    // 结束进程组组长时其子进程也被结束，不会遗留uvicorn的服务进程
+    #[test]
+    fn kill_pid_terminates_whole_process_group() {
+        let mut leader = Command::new("sh")
+            .args(["-c", "sleep 60 & echo $!; wait"])
+            .stdout(Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let mut line = String::new();
+        BufReader::new(leader.stdout.take().unwrap()).read_line(&mut line).unwrap();
+        let child_pid: u32 = line.trim().parse().unwrap();
+        
+        // 组长是本测试进程的子进程，需要回收后is_process_alive才会返回false
+        let pid = leader.id();
+        let reaper = thread::spawn(move || leader.wait());
+        
+        kill_pid(pid, Duration::from_secs(1)).unwrap();
+        reaper.join().unwrap().unwrap();
+        assert!(wait_for_pid_exit(child_pid, KILL_WAIT), "子进程{}仍在运行", child_pid);
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::{HashMap, HashSet};
+use serde::Serialize;
+
+use crate::error::JarvisError;
+
+// 监听指定端口的进程，无权限查看其他用户的进程时pid和name为None
+#[derive(Debug, Clone, Serialize)]
+pub struct PortHolder {
+    pub pid: Option<u32>,
+    pub name: Option<String>,
+    pub address: String,
+}
+
+// 列出监听指定TCP端口的进程，端口空闲时返回空列表
+// 通过netstat2调用各系统的底层接口(Linux的sock_diag、macOS的libproc、Windows的IP Helper)，
+// 不依赖lsof/netstat等外部命令
+pub fn who_has_port(port: u16) -> Result<Vec<PortHolder>, JarvisError> {
+    use netstat2::{AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, TcpState};
+    
+    let sockets = netstat2::get_sockets_info(
+        AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6,
+        ProtocolFlags::TCP,
+    )
+    .map_err(|e| JarvisError::Io(format!("查询端口{}的占用情况失败: {}", port, e)))?;
+    
+    let listeners: Vec<(String, Vec<u32>)> = sockets
+        .into_iter()
+        .filter_map(|socket| match socket.protocol_socket_info {
+            ProtocolSocketInfo::Tcp(tcp) => Some((tcp, socket.associated_pids)),
+            ProtocolSocketInfo::Udp(_) => None,
+        })
+        .filter(|(tcp, _)| tcp.local_port == port && tcp.state == TcpState::Listen)
+        .map(|(tcp, pids)| (std::net::SocketAddr::new(tcp.local_addr, port).to_string(), pids))
+        .collect();
+    if listeners.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let pids = listeners.iter().flat_map(|(_, pids)| pids.iter().copied()).collect();
+    let names = process_names(pids);
+    Ok(listeners
+        .into_iter()
+        .flat_map(|(address, pids)| {
+            // 拿不到PID时仍返回监听地址，让调用方知道端口已被占用
+            if pids.is_empty() {
+                return vec![PortHolder {
+                    pid: None,
+                    name: None,
+                    address,
+                }];
+            }
+            pids.into_iter()
+                .map(|pid| PortHolder {
+                    pid: Some(pid),
+                    name: names.get(&pid).cloned(),
+                    address: address.clone(),
+                })
+                .collect()
+        })
+        .collect())
+}
+
+// 通过sysinfo查询进程名，已退出或无权限查看的进程会被跳过
+fn process_names(pids: HashSet<u32>) -> HashMap<u32, String> {
+    use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+    
+    let pids: Vec<Pid> = pids.into_iter().map(Pid::from_u32).collect();
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    pids.iter()
+        .filter_map(|pid| {
+            let process = system.process(*pid)?;
+            Some((pid.as_u32(), process.name().to_string_lossy().into_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 能找到本进程监听的端口，空闲端口返回空列表
+    #[test]
+    fn finds_own_listening_port() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        
+        let holders = who_has_port(port).unwrap();
+        let holder = holders
+            .iter()
+            .find(|holder| holder.pid == Some(std::process::id()))
+            .expect("未找到本进程");
+        assert_eq!(holder.address, format!("127.0.0.1:{}", port));
+        assert!(holder.name.is_some());
+        
+        drop(listener);
+        assert!(who_has_port(port).unwrap().is_empty());
+    }
+}
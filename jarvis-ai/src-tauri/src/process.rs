@@ -0,0 +1,595 @@
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::JarvisError;
+use crate::lifecycle::{emit_failure, emit_lifecycle, JarvisLifecycle};
+use crate::logs::LogStream;
+use crate::{
+    ensure_python_version, forward_output, integrity, lock, pidfile, resolve_core_script,
+    resolve_python_executable, uds, AppState, ExitInfo, ExitedEvent, ServiceInstance,
+    PROCESS_EXIT_TIMEOUT, PYTHON_UTF8_ENV,
+};
+
+// 启动前检测端口占用的超时时间
+const PORT_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// 守护线程检查进程状态的间隔
+const SUPERVISOR_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// 默认的自动重启策略：窗口期内最多重启5次，退避时间从1秒开始翻倍
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+const DEFAULT_RESTART_WINDOW_SECS: u64 = 60;
+const DEFAULT_RESTART_BACKOFF_BASE_MS: u64 = 1000;
+
+// 自动重启的最长退避时间
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
+// 端口占用情况
+pub enum PortOccupant {
+    Free,
+    Jarvis,
+    Other,
+}
+
+// 检查端口是否已有进程监听，并通过就绪检查接口(默认/)的响应区分是否为JARVIS服务
+pub fn probe_port(addr: SocketAddr, readiness_path: &str) -> PortOccupant {
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, PORT_PROBE_TIMEOUT) else {
+        return PortOccupant::Free;
+    };
+    
+    let _ = stream.set_read_timeout(Some(PORT_PROBE_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(PORT_PROBE_TIMEOUT));
+    
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", readiness_path, addr);
+    let mut response = Vec::new();
+    if stream.write_all(request.as_bytes()).is_ok() {
+        let _ = stream.take(64 * 1024).read_to_end(&mut response);
+    }
+    
+    if String::from_utf8_lossy(&response).contains("JARVIS AI Core") {
+        PortOccupant::Jarvis
+    } else {
+        PortOccupant::Other
+    }
+}
+
+// jarvis-integrity-failed事件内容
+#[derive(Clone, Serialize)]
+struct IntegrityFailedEvent {
+    instance: String,
+    script: String,
+    expected: String,
+    actual: String,
+}
+
+// 配置了期望哈希时校验核心脚本，不一致时发送jarvis-integrity-failed事件并拒绝启动
+fn verify_core_integrity(
+    app: &AppHandle,
+    instance: &ServiceInstance,
+    script: &Path,
+) -> Result<(), JarvisError> {
+    let app_state = app.state::<AppState>();
+    let Some(expected) = lock(&app_state.expected_core_hash).clone() else {
+        return Ok(());
+    };
+    
+    let actual = integrity::sha256_file(script)?;
+    if actual == expected {
+        return Ok(());
+    }
+    
+    let event = IntegrityFailedEvent {
+        instance: instance.name.clone(),
+        script: script.display().to_string(),
+        expected: expected.clone(),
+        actual: actual.clone(),
+    };
+    let _ = app.emit("jarvis-integrity-failed", event);
+    Err(JarvisError::IntegrityCheckFailed(format!(
+        "JARVIS核心脚本{}的SHA-256与期望值不一致(期望{}，实际{})，可能已被修改或未完整更新",
+        script.display(),
+        expected,
+        actual
+    )))
+}
+
+// jarvis-security-warning事件内容
+#[derive(Clone, Serialize)]
+struct SecurityWarningEvent {
+    instance: String,
+    bind_address: String,
+    message: String,
+}
+
+// 启动核心进程所用的命令及参数
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchPlan {
+    program: String,
+    args: Vec<String>,
+    script: PathBuf,
+    working_dir: PathBuf,
+    port: u16,
+    bind_address: IpAddr,
+    unix_socket: Option<PathBuf>,
+}
+
+// 执行启动前的全部检查(Python、核心脚本、端口)，返回将要使用的启动命令
+pub fn plan_launch(app: &AppHandle, instance: &ServiceInstance) -> Result<LaunchPlan, JarvisError> {
+    let app_state = app.state::<AppState>();
+    let (jarvis_core_path, working_dir) = resolve_core_script(app)?;
+    
+    if !jarvis_core_path.exists() {
+        return Err(JarvisError::ScriptMissing(format!(
+            "JARVIS核心服务文件不存在: {}",
+            jarvis_core_path.display()
+        )));
+    }
+    verify_core_integrity(app, instance, &jarvis_core_path)?;
+    
+    // 配置了启动命令模板时按模板启动，不再检测Python
+    let port = *lock(&instance.port);
+    let launch_command = lock(&app_state.launch_command).clone();
+    let (program, args) = match launch_command {
+        Some(template) => expand_launch_command(&template, &jarvis_core_path, port)?,
+        None => {
+            let python_executable = resolve_python_executable(&app_state)?;
+            ensure_python_version(&python_executable)?;
+            let mut args = vec![jarvis_core_path.to_string_lossy().into_owned()];
+            args.extend(lock(&app_state.extra_args).iter().cloned());
+            (python_executable, args)
+        }
+    };
+    
+    let unix_socket = lock(&instance.unix_socket).clone();
+    let readiness_path = lock(&app_state.readiness_path).clone();
+    match unix_socket.as_deref() {
+        Some(path) => uds::prepare_socket(path)?,
+        None => ensure_port_free(instance.local_addr(), &readiness_path)?,
+    }
+    
+    Ok(LaunchPlan {
+        program,
+        args,
+        script: jarvis_core_path,
+        working_dir,
+        port,
+        bind_address: *lock(&instance.bind_address),
+        unix_socket,
+    })
+}
+
+// 检查端口是否可用
+fn ensure_port_free(addr: SocketAddr, readiness_path: &str) -> Result<(), JarvisError> {
+    let port = addr.port();
+    match probe_port(addr, readiness_path) {
+        PortOccupant::Free => Ok(()),
+        PortOccupant::Jarvis => {
+            Err(JarvisError::PortInUse(format!("端口{}上已有JARVIS实例在运行", port)))
+        }
+        PortOccupant::Other => Err(JarvisError::PortInUse(format!(
+            "端口{}已被其他程序占用，请更换端口或关闭占用该端口的程序",
+            port
+        ))),
+    }
+}
+
+// 启动实例的JARVIS核心进程
+pub fn spawn_jarvis_process(app: &AppHandle, instance: &ServiceInstance) -> Result<Child, JarvisError> {
+    let app_state = app.state::<AppState>();
+    let plan = plan_launch(app, instance)?;
+    
+    let detached = *lock(&app_state.detached);
+    let mut command = Command::new(&plan.program);
+    command
+        .args(&plan.args)
+        .envs(PYTHON_UTF8_ENV)
+        .envs(lock(&app_state.env_vars).iter())
+        .env("JARVIS_PORT", plan.port.to_string())
+        .env("JARVIS_HOST", plan.bind_address.to_string())
+        .current_dir(&plan.working_dir);
+    
+    if detached {
+        configure_detached(app, &instance.name, &mut command)?;
+    } else {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        // 在独立的进程组中运行，强制结束时可一并结束核心启动的子进程
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    }
+    
+    // 核心读取JARVIS_UDS后改为监听Unix socket
+    if let Some(path) = &plan.unix_socket {
+        command.env("JARVIS_UDS", path);
+    }
+    
+    let mut child = command
+        .spawn()
+        .map_err(|e| JarvisError::SpawnFailed(format!("启动JARVIS服务失败: {}", e)))?;
+    
+    if let Err(e) = pidfile::write_pid(app, &instance.name, child.id()) {
+        app_state.log_app(&instance.name, e);
+    }
+    *lock(&instance.launched_detached) = detached;
+    
+    // 核心本身没有鉴权，监听非回环地址时局域网内的其他设备也能访问
+    if plan.unix_socket.is_none() && !plan.bind_address.is_loopback() {
+        let event = SecurityWarningEvent {
+            instance: instance.name.clone(),
+            bind_address: plan.bind_address.to_string(),
+            message: format!(
+                "JARVIS服务监听在{}，其他设备可能无需鉴权即可访问",
+                plan.bind_address
+            ),
+        };
+        let _ = app.emit("jarvis-security-warning", event);
+    }
+    
+    // 读取输出，避免管道缓冲区写满阻塞Python进程
+    if let Some(stdout) = child.stdout.take() {
+        forward_output(app.clone(), stdout, LogStream::Stdout, instance.name.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_output(app.clone(), stderr, LogStream::Stderr, instance.name.clone());
+    }
+    
+    Ok(child)
+}
+
+// 独立模式：核心在新的会话(Windows为DETACHED_PROCESS)中运行，输出写入日志文件而不是管道，
+// 应用退出后进程不会因终端关闭或管道断开而退出；代价是输出不再进入应用的日志缓冲区
+fn configure_detached(
+    app: &AppHandle,
+    instance: &str,
+    command: &mut Command,
+) -> Result<(), JarvisError> {
+    let log = pidfile::open_detached_log(app, instance).map_err(JarvisError::Io)?;
+    let stderr = log
+        .try_clone()
+        .map_err(|e| JarvisError::Io(format!("打开核心输出日志失败: {}", e)))?;
+    command.stdin(Stdio::null()).stdout(log).stderr(stderr);
+    
+    // setsid后进程成为新会话和进程组的组长，kill_process_tree仍可结束整个进程组
+    #[cfg(unix)]
+    unsafe {
+        std::os::unix::process::CommandExt::pre_exec(command, || {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    
+    #[cfg(windows)]
+    {
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        std::os::windows::process::CommandExt::creation_flags(
+            command,
+            DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP,
+        );
+    }
+    Ok(())
+}
+
+// 检查启动命令模板：第一项为程序，其余为参数，且必须引用{script}占位符
+pub fn validate_launch_command(template: &[String]) -> Result<(), JarvisError> {
+    match template.first() {
+        None => return Err(JarvisError::InvalidArgument("启动命令不能为空".to_string())),
+        Some(program) if program.trim().is_empty() => {
+            return Err(JarvisError::InvalidArgument("启动命令的程序不能为空".to_string()));
+        }
+        Some(_) => {}
+    }
+    if !template.iter().any(|part| part.contains("{script}")) {
+        return Err(JarvisError::InvalidArgument("启动命令必须包含{script}占位符".to_string()));
+    }
+    Ok(())
+}
+
+// 展开启动命令模板，替换其中的{script}和{port}占位符，返回程序及参数(不经过shell解析)
+fn expand_launch_command(
+    template: &[String],
+    script: &Path,
+    port: u16,
+) -> Result<(String, Vec<String>), JarvisError> {
+    validate_launch_command(template)?;
+    let script = script.to_string_lossy();
+    let port = port.to_string();
+    let mut parts = template
+        .iter()
+        .map(|part| part.replace("{script}", &script).replace("{port}", &port));
+    
+    let program = parts.next().unwrap_or_default();
+    Ok((program, parts.collect()))
+}
+
+// 进程崩溃后的自动重启策略
+// 窗口期内重启次数超过max_restarts时放弃，max_restarts为0表示不自动重启
+// 进程持续运行超过窗口期后重启计数清零
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RestartPolicy {
+    pub max_restarts: u32,
+    pub restart_window_secs: u64,
+    pub backoff_base_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: DEFAULT_MAX_RESTARTS,
+            restart_window_secs: DEFAULT_RESTART_WINDOW_SECS,
+            backoff_base_ms: DEFAULT_RESTART_BACKOFF_BASE_MS,
+        }
+    }
+}
+
+impl RestartPolicy {
+    // 第attempt次重启前的退避时间：base、2*base、4*base……最长30秒
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_millis(self.backoff_base_ms.saturating_mul(factor)).min(MAX_RESTART_BACKOFF)
+    }
+    
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.restart_window_secs)
+    }
+}
+
+// jarvis-restarted事件内容
+#[derive(Clone, Serialize)]
+struct RestartedEvent {
+    instance: String,
+    attempt: u32,
+}
+
+// jarvis-giving-up事件内容，restarts为窗口期内已尝试的重启次数
+#[derive(Clone, Serialize)]
+struct GivingUpEvent {
+    instance: String,
+    restarts: u32,
+}
+
+// 启动实例的进程守护线程，检测到进程意外退出时自动重新拉起
+pub fn spawn_supervisor(app: AppHandle, instance: Arc<ServiceInstance>) {
+    let generation = {
+        let mut generation = lock(&instance.supervisor_generation);
+        *generation += 1;
+        *generation
+    };
+    
+    thread::spawn(move || {
+        // 新的守护线程启动或服务被停止后当前线程退出
+        let is_current = || {
+            *lock(&instance.supervisor_generation) == generation
+                && instance.is_running()
+        };
+        let mut running_since = Instant::now();
+        
+        loop {
+            thread::sleep(SUPERVISOR_POLL_INTERVAL);
+            if !is_current() {
+                return;
+            }
+            
+            // 进程稳定运行超过窗口期后重新计算重启次数
+            let policy = *lock(&app.state::<AppState>().restart_policy);
+            if running_since.elapsed() > policy.window() {
+                *lock(&instance.restart_count) = 0;
+            }
+            
+            if !instance.has_child() {
+                return;
+            }
+            let Some(status) = instance.reap_exited() else {
+                continue;
+            };
+            let exit = ExitInfo::from_status(status);
+            *lock(&instance.last_exit) = Some(exit.clone());
+            let error = JarvisError::Crashed(exit.describe());
+            emit_failure(&app, &instance.name, JarvisLifecycle::Crashed, &error);
+            let event = ExitedEvent {
+                instance: instance.name.clone(),
+                exit,
+            };
+            let _ = app.emit("jarvis-exited", event);
+            
+            // 进程意外退出，按重启策略重新启动
+            loop {
+                let policy = *lock(&app.state::<AppState>().restart_policy);
+                if policy.max_restarts == 0 {
+                    instance.set_running(false);
+                    emit_lifecycle(
+                        &app,
+                        &instance.name,
+                        JarvisLifecycle::Stopped,
+                        Some("未开启自动重启".to_string()),
+                    );
+                    return;
+                }
+                
+                let attempt = {
+                    let mut restart_count = lock(&instance.restart_count);
+                    *restart_count += 1;
+                    *restart_count
+                };
+                
+                if attempt > policy.max_restarts {
+                    instance.set_running(false);
+                    emit_lifecycle(
+                        &app,
+                        &instance.name,
+                        JarvisLifecycle::Stopped,
+                        Some(format!("已达到最大自动重启次数({})", policy.max_restarts)),
+                    );
+                    let event = GivingUpEvent {
+                        instance: instance.name.clone(),
+                        restarts: policy.max_restarts,
+                    };
+                    let _ = app.emit("jarvis-giving-up", event);
+                    return;
+                }
+                
+                thread::sleep(policy.backoff(attempt));
+                
+                // 等待期间可能已被停止或由其他命令重新启动
+                let _operation = lock(&instance.process_op);
+                if !is_current() || instance.has_child() {
+                    return;
+                }
+                
+                emit_lifecycle(
+                    &app,
+                    &instance.name,
+                    JarvisLifecycle::Starting,
+                    Some(format!("第{}次自动重启", attempt)),
+                );
+                match spawn_jarvis_process(&app, &instance) {
+                    Ok(child) => {
+                        instance.store_process(child);
+                        running_since = Instant::now();
+                        emit_lifecycle(&app, &instance.name, JarvisLifecycle::Started, None);
+                        let event = RestartedEvent {
+                            instance: instance.name.clone(),
+                            attempt,
+                        };
+                        let _ = app.emit("jarvis-restarted", event);
+                        break;
+                    }
+                    Err(e) => {
+                        emit_failure(&app, &instance.name, JarvisLifecycle::Failed, &e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+// 等待进程退出，超时则返回错误
+pub fn wait_for_exit(child: &mut Child, timeout: Duration) -> Result<ExitStatus, JarvisError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(status),
+            Ok(None) if Instant::now() >= deadline => {
+                return Err(JarvisError::Timeout(format!(
+                    "等待JARVIS进程退出超时({}秒)",
+                    timeout.as_secs()
+                )));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => {
+                return Err(JarvisError::ProcessControlFailed(format!("等待JARVIS进程退出失败: {}", e)));
+            }
+        }
+    }
+}
+
+// 进程停止方式
+pub enum ShutdownKind {
+    Graceful,
+    Forced,
+}
+
+// 请求进程正常退出(Unix发送SIGTERM)
+#[cfg(unix)]
+fn request_terminate(child: &Child) -> Result<(), JarvisError> {
+    let pid = child.id() as libc::pid_t;
+    if unsafe { libc::kill(pid, libc::SIGTERM) } == 0 {
+        Ok(())
+    } else {
+        Err(JarvisError::ProcessControlFailed(format!(
+            "发送SIGTERM失败: {}",
+            std::io::Error::last_os_error()
+        )))
+    }
+}
+
+// 通知核心重新加载配置和模型(向进程组发送SIGHUP)，核心未处理该信号时进程会退出
+// 核心进程启动时总是进程组组长，reload模式下uvicorn的服务进程在同一进程组中，也能收到信号
+#[cfg(unix)]
+pub fn send_reload_signal(pid: u32) -> Result<(), JarvisError> {
+    if unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGHUP) } == 0 {
+        Ok(())
+    } else {
+        Err(JarvisError::ProcessControlFailed(format!(
+            "发送SIGHUP失败: {}",
+            std::io::Error::last_os_error()
+        )))
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send_reload_signal(_pid: u32) -> Result<(), JarvisError> {
+    Err(JarvisError::NotSupported("当前系统不支持通过信号重新加载，请改用接口方式".to_string()))
+}
+
+// 请求进程正常退出(Windows使用不带/F的taskkill)
+#[cfg(windows)]
+fn request_terminate(child: &Child) -> Result<(), JarvisError> {
+    let status = Command::new("taskkill")
+        .args(["/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| JarvisError::ProcessControlFailed(format!("执行taskkill失败: {}", e)))?;
+    
+    if status.success() {
+        Ok(())
+    } else {
+        Err(JarvisError::ProcessControlFailed(format!("taskkill执行失败: {}", status)))
+    }
+}
+
+// 停止进程：先请求正常退出，超过宽限期仍未退出再强制结束
+pub fn shutdown_process(child: &mut Child, grace: Duration) -> Result<(ShutdownKind, ExitStatus), JarvisError> {
+    if request_terminate(child).is_ok() {
+        if let Ok(status) = wait_for_exit(child, grace) {
+            return Ok((ShutdownKind::Graceful, status));
+        }
+    }
+    
+    child
+        .kill()
+        .map_err(|e| JarvisError::ProcessControlFailed(format!("停止JARVIS服务失败: {}", e)))?;
+    let status = wait_for_exit(child, PROCESS_EXIT_TIMEOUT)?;
+    Ok((ShutdownKind::Forced, status))
+}
+
+// 强制结束进程及其子进程(Unix向整个进程组发送SIGKILL)
+#[cfg(unix)]
+pub fn kill_process_tree(child: &mut Child) -> Result<(), JarvisError> {
+    // 核心进程以自身PID作为进程组ID启动
+    let pgid = child.id() as libc::pid_t;
+    if unsafe { libc::kill(-pgid, libc::SIGKILL) } == 0 {
+        return Ok(());
+    }
+    
+    // 进程组不存在时退回到只结束该进程
+    child.kill().map_err(|e| {
+        JarvisError::ProcessControlFailed(format!("强制结束JARVIS进程失败: {}", e))
+    })
+}
+
+// 强制结束进程及其子进程(Windows使用taskkill /F /T)
+#[cfg(windows)]
+pub fn kill_process_tree(child: &mut Child) -> Result<(), JarvisError> {
+    let status = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        _ => child.kill().map_err(|e| {
+            JarvisError::ProcessControlFailed(format!("强制结束JARVIS进程失败: {}", e))
+        }),
+    }
+}
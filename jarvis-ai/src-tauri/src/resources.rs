@@ -0,0 +1,32 @@
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+// 核心进程的资源占用
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceStats {
+    pub pid: u32,
+    // 相对单个CPU核心的占用百分比，多线程时可能超过100
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    // 仅Linux可获取线程数
+    pub thread_count: Option<usize>,
+}
+
+// 采集进程的资源占用，进程不存在时返回None
+// CPU占用按与上一次采集的间隔计算，因此System需要在多次采集间复用
+pub fn sample(system: &mut System, pid: u32) -> Option<ResourceStats> {
+    let pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing().with_cpu().with_memory().with_tasks(),
+    );
+    
+    let process = system.process(pid)?;
+    Some(ResourceStats {
+        pid: pid.as_u32(),
+        cpu_percent: process.cpu_usage(),
+        memory_bytes: process.memory(),
+        thread_count: process.tasks().map(|tasks| tasks.len()),
+    })
+}
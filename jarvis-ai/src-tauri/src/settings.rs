@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::JarvisError;
+use crate::process::{validate_launch_command, RestartPolicy};
+use crate::{
+    config, detect_python_version, instance_name, integrity, lock, resolve_core_script,
+    resolve_python_executable, run_blocking, uds, validate_http_path, validate_port, venv_python,
+    AppState, DEFAULT_HEALTH_PATH, DEFAULT_READINESS_PATH, MAX_CONCURRENT_REQUESTS_LIMIT,
+    MAX_SHUTDOWN_GRACE_SECS,
+};
+
+// 启动参数中不允许出现的shell元字符
+const SHELL_METACHARACTERS: &[char] = &[';', '&', '|', '$', '`', '<', '>', '\n', '\r', '\0'];
+
+// 设置传给JARVIS核心的环境变量，值为空字符串表示删除该变量，下次启动时生效
+#[tauri::command]
+pub async fn set_jarvis_env(
+    app_state: tauri::State<'_, AppState>,
+    vars: HashMap<String, String>,
+) -> Result<String, JarvisError> {
+    if let Some(key) = vars
+        .keys()
+        .find(|key| key.is_empty() || key.contains(['=', '\0']))
+    {
+        return Err(JarvisError::InvalidArgument(format!("无效的环境变量名: {:?}", key)));
+    }
+    
+    let mut env_vars = lock(&app_state.env_vars);
+    for (key, value) in vars {
+        if value.is_empty() {
+            env_vars.remove(&key);
+        } else {
+            env_vars.insert(key, value);
+        }
+    }
+    Ok(format!("已设置{}个环境变量", env_vars.len()))
+}
+
+// 设置追加在核心脚本路径之后的命令行参数，下次启动时生效
+// 参数原样传给Python进程，不经过shell解析；仍拒绝shell元字符，避免参数被转交给shell时被误用
+#[tauri::command]
+pub async fn set_core_args(
+    app_state: tauri::State<'_, AppState>,
+    args: Vec<String>,
+) -> Result<String, JarvisError> {
+    if let Some(arg) = args.iter().find(|arg| arg.contains(SHELL_METACHARACTERS)) {
+        return Err(JarvisError::InvalidArgument(format!("参数包含不允许的字符: {:?}", arg)));
+    }
+    
+    // 运行中修改参数不会生效，要求先停止服务
+    if app_state
+        .all_instances()
+        .iter()
+        .any(|instance| instance.has_process())
+    {
+        return Err(JarvisError::AlreadyRunning(
+            "JARVIS服务正在运行，请先停止服务再修改启动参数".to_string(),
+        ));
+    }
+    
+    let count = args.len();
+    *lock(&app_state.extra_args) = args;
+    Ok(format!("已设置{}个启动参数", count))
+}
+
+// 设置启动JARVIS核心的命令模板，例如["poetry", "run", "python", "{script}", "--port", "{port}"]
+// 设置后不再自动检测Python，也不追加set_core_args设置的参数；传入None恢复默认的启动方式
+#[tauri::command]
+pub async fn set_launch_command(
+    app_state: tauri::State<'_, AppState>,
+    command: Option<Vec<String>>,
+) -> Result<String, JarvisError> {
+    if let Some(template) = command.as_deref() {
+        validate_launch_command(template)?;
+    }
+    
+    let message = match &command {
+        Some(template) => format!("启动命令已设置为: {}", template.join(" ")),
+        None => "已恢复默认的启动方式".to_string(),
+    };
+    *lock(&app_state.launch_command) = command;
+    Ok(message)
+}
+
+// 删除传给JARVIS核心的环境变量
+#[tauri::command]
+pub async fn unset_jarvis_env(app_state: tauri::State<'_, AppState>, key: String) -> Result<String, JarvisError> {
+    match lock(&app_state.env_vars).remove(&key) {
+        Some(_) => Ok(format!("环境变量{}已删除", key)),
+        None => Ok(format!("环境变量{}未设置", key)),
+    }
+}
+
+// 设置判定服务卡死的时长(秒)：超过该时间没有成功的健康检查时发送jarvis-stalled事件
+#[tauri::command]
+pub async fn set_stall_threshold(
+    app_state: tauri::State<'_, AppState>,
+    secs: u64,
+) -> Result<String, JarvisError> {
+    if secs == 0 {
+        return Err(JarvisError::InvalidArgument("卡死判定时长必须大于0".to_string()));
+    }
+    
+    *lock(&app_state.stall_threshold_secs) = secs;
+    Ok(format!("卡死判定时长已设置为{}秒", secs))
+}
+
+// 设置同时转发给核心的请求数上限(1到64)，单线程模型可设为1
+#[tauri::command]
+pub async fn set_max_concurrent_requests(
+    app_state: tauri::State<'_, AppState>,
+    permits: usize,
+) -> Result<String, JarvisError> {
+    if !(1..=MAX_CONCURRENT_REQUESTS_LIMIT).contains(&permits) {
+        return Err(JarvisError::InvalidArgument(format!(
+            "并发请求上限必须在1到{}之间",
+            MAX_CONCURRENT_REQUESTS_LIMIT
+        )));
+    }
+    
+    app_state.set_max_concurrent_requests(permits);
+    Ok(format!("并发请求上限已设置为{}", permits))
+}
+
+// 设置后台状态检查间隔(秒)
+#[tauri::command]
+pub async fn set_status_poll_interval(
+    app_state: tauri::State<'_, AppState>,
+    secs: u64,
+) -> Result<String, JarvisError> {
+    if secs == 0 {
+        return Err(JarvisError::InvalidArgument("状态检查间隔必须大于0".to_string()));
+    }
+    
+    *lock(&app_state.status_poll_interval_secs) = secs;
+    Ok(format!("状态检查间隔已设置为{}秒", secs))
+}
+
+// 设置启动相关的等待时间，未指定的项保持不变
+// ready_timeout_secs为启动后等待服务就绪的超时时间，startup_delay_ms为应用启动后自动启动服务前的固定延迟
+#[tauri::command]
+pub async fn set_startup_timing(
+    app_state: tauri::State<'_, AppState>,
+    ready_timeout_secs: Option<u64>,
+    startup_delay_ms: Option<u64>,
+) -> Result<String, JarvisError> {
+    if ready_timeout_secs == Some(0) {
+        return Err(JarvisError::InvalidArgument("就绪超时时间必须大于0".to_string()));
+    }
+    
+    if let Some(secs) = ready_timeout_secs {
+        *lock(&app_state.ready_timeout_secs) = secs;
+    }
+    if let Some(ms) = startup_delay_ms {
+        *lock(&app_state.startup_delay_ms) = ms;
+    }
+    Ok(format!(
+        "就绪超时时间为{}秒，自动启动延迟为{}毫秒",
+        *lock(&app_state.ready_timeout_secs),
+        *lock(&app_state.startup_delay_ms)
+    ))
+}
+
+// 设置崩溃后的自动重启策略，未指定的项保持不变，返回修改后的策略
+// max_restarts为0时关闭自动重启
+#[tauri::command]
+pub async fn set_restart_policy(
+    app_state: tauri::State<'_, AppState>,
+    max_restarts: Option<u32>,
+    restart_window_secs: Option<u64>,
+    backoff_base_ms: Option<u64>,
+) -> Result<RestartPolicy, JarvisError> {
+    if restart_window_secs == Some(0) {
+        return Err(JarvisError::InvalidArgument("重启窗口期必须大于0".to_string()));
+    }
+    if backoff_base_ms == Some(0) {
+        return Err(JarvisError::InvalidArgument("重启退避时间必须大于0".to_string()));
+    }
+    
+    let mut policy = lock(&app_state.restart_policy);
+    if let Some(max_restarts) = max_restarts {
+        policy.max_restarts = max_restarts;
+    }
+    if let Some(secs) = restart_window_secs {
+        policy.restart_window_secs = secs;
+    }
+    if let Some(ms) = backoff_base_ms {
+        policy.backoff_base_ms = ms;
+    }
+    Ok(*policy)
+}
+
+// 设置停止服务时等待进程正常退出的宽限期(秒)，超过上限时按上限处理，立即写入配置文件
+// 关闭应用时同样会等待该宽限期，核心无响应时较长的宽限期会拖慢退出
+#[tauri::command]
+pub async fn set_shutdown_grace(
+    app: AppHandle,
+    app_state: tauri::State<'_, AppState>,
+    secs: u64,
+) -> Result<String, JarvisError> {
+    let secs = secs.min(MAX_SHUTDOWN_GRACE_SECS);
+    config::update(&app, |config| config.shutdown_grace_secs = secs).map_err(JarvisError::Io)?;
+    *lock(&app_state.shutdown_grace_secs) = secs;
+    Ok(format!("停止服务的宽限期已设置为{}秒", secs))
+}
+
+// 暂停后台状态及资源检查，核心进程继续运行
+#[tauri::command]
+pub async fn pause_monitoring(app: AppHandle, app_state: tauri::State<'_, AppState>) -> Result<String, JarvisError> {
+    *lock(&app_state.monitoring_paused) = true;
+    let _ = app.emit("monitoring-paused", ());
+    Ok("已暂停后台状态检查".to_string())
+}
+
+// 恢复后台状态及资源检查，在下一个检查周期生效
+#[tauri::command]
+pub async fn resume_monitoring(app: AppHandle, app_state: tauri::State<'_, AppState>) -> Result<String, JarvisError> {
+    *lock(&app_state.monitoring_paused) = false;
+    let _ = app.emit("monitoring-resumed", ());
+    Ok("已恢复后台状态检查".to_string())
+}
+
+// 设置健康检查及就绪检查使用的接口路径，未指定时恢复默认值
+#[tauri::command]
+pub async fn set_health_paths(
+    app_state: tauri::State<'_, AppState>,
+    health_path: Option<String>,
+    readiness_path: Option<String>,
+) -> Result<String, JarvisError> {
+    let health_path = health_path.unwrap_or_else(|| DEFAULT_HEALTH_PATH.to_string());
+    let readiness_path = readiness_path.unwrap_or_else(|| DEFAULT_READINESS_PATH.to_string());
+    validate_http_path(&health_path)?;
+    validate_http_path(&readiness_path)?;
+    
+    let message = format!("健康检查接口: {}，就绪检查接口: {}", health_path, readiness_path);
+    *lock(&app_state.health_path) = health_path;
+    *lock(&app_state.readiness_path) = readiness_path;
+    Ok(message)
+}
+
+// 本地服务的传输方式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Transport {
+    Tcp { port: u16 },
+    Unix { path: PathBuf },
+}
+
+// 设置本地服务的传输方式：TCP端口或Unix socket(仅限Unix系统)，下次启动时生效
+#[tauri::command]
+pub async fn set_transport(
+    app_state: tauri::State<'_, AppState>,
+    transport: Transport,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let instance = app_state.instance(&instance_name(instance)?);
+    if instance.has_process() {
+        return Err(JarvisError::AlreadyRunning(
+            "JARVIS服务正在运行，请先停止服务再切换传输方式".to_string(),
+        ));
+    }
+    
+    match transport {
+        Transport::Tcp { port } => {
+            validate_port(port)?;
+            *lock(&instance.port) = port;
+            *lock(&instance.unix_socket) = None;
+            Ok(format!("已切换为TCP传输，端口{}", port))
+        }
+        Transport::Unix { path } => {
+            if !cfg!(unix) {
+                return Err(uds::unsupported());
+            }
+            if !path.is_absolute() {
+                return Err(JarvisError::InvalidArgument("socket路径必须为绝对路径".to_string()));
+            }
+            let message = format!("已切换为Unix socket传输: {}", path.display());
+            *lock(&instance.unix_socket) = Some(path);
+            Ok(message)
+        }
+    }
+}
+
+// 设置本地服务的监听地址(如0.0.0.0以便局域网访问)，下次启动时生效
+#[tauri::command]
+pub async fn set_bind_address(
+    app_state: tauri::State<'_, AppState>,
+    address: String,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    let address: IpAddr = address
+        .trim()
+        .parse()
+        .map_err(|_| JarvisError::InvalidArgument(format!("无效的IP地址: {}", address)))?;
+    let instance = app_state.instance(&instance_name(instance)?);
+    if instance.has_process() {
+        return Err(JarvisError::AlreadyRunning(
+            "JARVIS服务正在运行，请先停止服务再修改监听地址".to_string(),
+        ));
+    }
+    
+    *lock(&instance.bind_address) = address;
+    if address.is_loopback() {
+        Ok(format!("监听地址已设置为{}", address))
+    } else {
+        Ok(format!("监听地址已设置为{}，注意核心服务没有鉴权，其他设备也可访问", address))
+    }
+}
+
+// 设置访问JARVIS服务的Bearer令牌，传入None或空字符串时清除；令牌不会出现在日志和事件中
+#[tauri::command]
+pub async fn set_auth_token(
+    app_state: tauri::State<'_, AppState>,
+    token: Option<String>,
+) -> Result<String, JarvisError> {
+    let token = token.map(|token| token.trim().to_string()).filter(|token| !token.is_empty());
+    let Some(token) = token else {
+        *lock(&app_state.auth_token) = None;
+        return Ok("已清除访问令牌".to_string());
+    };
+    if token.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return Err(JarvisError::InvalidArgument("访问令牌不能包含空白或控制字符".to_string()));
+    }
+    
+    *lock(&app_state.auth_token) = Some(token);
+    Ok("访问令牌已设置".to_string())
+}
+
+// 设置JARVIS服务端口，实例不存在时注册新实例，多个实例需各自设置不同的端口
+#[tauri::command]
+pub async fn set_jarvis_port(
+    app_state: tauri::State<'_, AppState>,
+    port: u16,
+    instance: Option<String>,
+) -> Result<String, JarvisError> {
+    validate_port(port)?;
+    let instance = app_state.instance(&instance_name(instance)?);
+    
+    // 服务运行中不允许修改端口
+    if instance.has_process() {
+        return Err(JarvisError::AlreadyRunning(
+            "JARVIS服务正在运行，请先停止服务再修改端口".to_string(),
+        ));
+    }
+    
+    *lock(&instance.port) = port;
+    Ok(format!("JARVIS服务端口已设置为{}", port))
+}
+
+// 检查Python版本
+#[tauri::command]
+pub async fn check_python_version(app_state: tauri::State<'_, AppState>) -> Result<String, JarvisError> {
+    let python_executable = resolve_python_executable(&app_state)?;
+    let (major, minor, patch) =
+        run_blocking(move || detect_python_version(&python_executable)).await?;
+    Ok(format!("{}.{}.{}", major, minor, patch))
+}
+
+// 设置Python可执行文件路径，传入None则恢复自动检测
+#[tauri::command]
+pub async fn set_python_path(
+    app_state: tauri::State<'_, AppState>,
+    path: Option<String>,
+) -> Result<String, JarvisError> {
+    let Some(path) = path.filter(|p| !p.trim().is_empty()) else {
+        *lock(&app_state.python_path) = None;
+        return Ok("已恢复自动检测Python".to_string());
+    };
+    
+    // 包含路径分隔符时按文件路径校验，否则视为PATH中的命令
+    if path.contains(['/', '\\']) && !Path::new(&path).is_file() {
+        return Err(JarvisError::PythonNotFound(format!("Python可执行文件不存在: {}", path)));
+    }
+    
+    {
+        let path = path.clone();
+        run_blocking(move || detect_python_version(&path)).await?;
+    }
+    
+    *lock(&app_state.python_path) = Some(path.clone());
+    Ok(format!("Python路径已设置为{}", path))
+}
+
+// 设置JARVIS核心脚本路径，传入None则恢复默认路径
+#[tauri::command]
+pub async fn set_core_script_path(
+    app_state: tauri::State<'_, AppState>,
+    path: Option<String>,
+) -> Result<String, JarvisError> {
+    let Some(path) = path.filter(|p| !p.trim().is_empty()) else {
+        *lock(&app_state.core_script_path) = None;
+        return Ok("已恢复默认JARVIS核心脚本路径".to_string());
+    };
+    
+    let script = PathBuf::from(&path);
+    if !script.is_file() {
+        return Err(JarvisError::ScriptMissing(format!("JARVIS核心脚本不存在: {}", path)));
+    }
+    
+    *lock(&app_state.core_script_path) = Some(script);
+    Ok(format!("JARVIS核心脚本路径已设置为{}", path))
+}
+
+// compute_core_hash的返回内容
+#[derive(Debug, Clone, Serialize)]
+pub struct CoreHash {
+    script: PathBuf,
+    sha256: String,
+}
+
+// 计算当前将要启动的核心脚本的SHA-256，用于记录set_expected_core_hash的基准值
+#[tauri::command]
+pub async fn compute_core_hash(app: AppHandle) -> Result<CoreHash, JarvisError> {
+    run_blocking(move || {
+        let (script, _) = resolve_core_script(&app)?;
+        let sha256 = integrity::sha256_file(&script)?;
+        Ok(CoreHash { script, sha256 })
+    })
+    .await
+}
+
+// 设置核心脚本期望的SHA-256，传入None或空字符串时不再校验
+#[tauri::command]
+pub async fn set_expected_core_hash(
+    app_state: tauri::State<'_, AppState>,
+    hash: Option<String>,
+) -> Result<String, JarvisError> {
+    let Some(hash) = hash.filter(|hash| !hash.trim().is_empty()) else {
+        *lock(&app_state.expected_core_hash) = None;
+        return Ok("已关闭核心脚本完整性校验".to_string());
+    };
+    
+    let hash = integrity::normalize_hash(&hash)?;
+    *lock(&app_state.expected_core_hash) = Some(hash.clone());
+    Ok(format!("核心脚本期望的SHA-256已设置为{}", hash))
+}
+
+// 设置Python虚拟环境路径，传入None则不使用虚拟环境
+#[tauri::command]
+pub async fn set_venv_path(
+    app_state: tauri::State<'_, AppState>,
+    path: Option<String>,
+) -> Result<String, JarvisError> {
+    let Some(path) = path.filter(|p| !p.trim().is_empty()) else {
+        *lock(&app_state.venv_path) = None;
+        return Ok("已停用虚拟环境".to_string());
+    };
+    
+    let venv = PathBuf::from(&path);
+    if !venv_python(&venv).is_file() {
+        return Err(JarvisError::VenvFailed(format!(
+            "虚拟环境中的Python解释器不存在: {}",
+            venv_python(&venv).display()
+        )));
+    }
+    
+    *lock(&app_state.venv_path) = Some(venv);
+    Ok(format!("虚拟环境已设置为{}", path))
+}
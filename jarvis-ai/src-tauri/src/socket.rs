@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header::AUTHORIZATION, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+// 连接断开后的重连间隔，按指数退避增长
+const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+// WebSocket连接状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketState {
+    Connecting,
+    Connected,
+    Disconnected,
+    Closed,
+}
+
+// jarvis-ws-state事件内容
+#[derive(Clone, Serialize)]
+struct SocketStateEvent<'a> {
+    state: SocketState,
+    url: &'a str,
+    reason: Option<String>,
+}
+
+// 后台WebSocket连接的句柄
+pub struct SocketHandle {
+    url: String,
+    sender: mpsc::UnboundedSender<String>,
+    task: JoinHandle<()>,
+}
+
+impl SocketHandle {
+    // 发送文本消息，连接断开期间的消息会在重连后发出
+    pub fn send(&self, text: String) -> bool {
+        self.sender.send(text).is_ok()
+    }
+    
+    // 关闭连接并停止重连
+    pub fn close(self, app: &AppHandle) {
+        self.task.abort();
+        emit_state(app, &self.url, SocketState::Closed, None);
+    }
+}
+
+// 建立到JARVIS核心的WebSocket连接，收到的消息以jarvis-ws-message事件转发给前端
+// 设置了auth_token时握手请求附加Authorization头，设置了unix_socket时经由该socket连接
+pub fn open(
+    app: AppHandle,
+    url: String,
+    auth_token: Option<String>,
+    unix_socket: Option<PathBuf>,
+) -> SocketHandle {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let task = run(app, url.clone(), auth_token, unix_socket, receiver);
+    let task = tauri::async_runtime::spawn(task);
+    SocketHandle { url, sender, task }
+}
+
+// 连接断开的原因，None表示句柄已释放、连接已主动关闭
+type Disconnect = Option<String>;
+
+// 建立一次WebSocket连接并转发消息直到连接断开
+async fn session(
+    app: &AppHandle,
+    url: &str,
+    auth_token: Option<&str>,
+    unix_socket: Option<&Path>,
+    outgoing: &mut mpsc::UnboundedReceiver<String>,
+) -> Result<Disconnect, String> {
+    let mut request = url.into_client_request().map_err(|e| e.to_string())?;
+    if let Some(token) = auth_token {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|_| "访问令牌包含无效字符".to_string())?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+    
+    match unix_socket {
+        Some(path) => {
+            let stream = connect_unix(path).await?;
+            let (stream, _) = tokio_tungstenite::client_async(request, stream)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(forward(app, url, stream, outgoing).await)
+        }
+        None => {
+            let (stream, _) = tokio_tungstenite::connect_async(request)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(forward(app, url, stream, outgoing).await)
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn connect_unix(path: &Path) -> Result<tokio::net::UnixStream, String> {
+    tokio::net::UnixStream::connect(path)
+        .await
+        .map_err(|e| format!("连接Unix socket {}失败: {}", path.display(), e))
+}
+
+// 非Unix系统无法切换为Unix socket传输，不会调用到这里
+#[cfg(not(unix))]
+async fn connect_unix(_path: &Path) -> Result<tokio::net::TcpStream, String> {
+    Err("当前系统不支持Unix socket".to_string())
+}
+
+// 双向转发消息，返回连接断开的原因
+async fn forward<S>(
+    app: &AppHandle,
+    url: &str,
+    stream: WebSocketStream<S>,
+    outgoing: &mut mpsc::UnboundedReceiver<String>,
+) -> Disconnect
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    emit_state(app, url, SocketState::Connected, None);
+    
+    let (mut write, mut read) = stream.split();
+    loop {
+        tokio::select! {
+            message = outgoing.recv() => match message {
+                Some(text) => {
+                    if let Err(e) = write.send(Message::text(text)).await {
+                        return Some(format!("发送消息失败: {}", e));
+                    }
+                }
+                // 句柄已释放，主动关闭连接
+                None => {
+                    let _ = write.send(Message::Close(None)).await;
+                    return None;
+                }
+            },
+            message = read.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let _ = app.emit("jarvis-ws-message", text.as_str());
+                }
+                Some(Ok(Message::Binary(data))) => {
+                    let _ = app.emit("jarvis-ws-message", String::from_utf8_lossy(&data));
+                }
+                Some(Ok(Message::Close(_))) => return Some("JARVIS服务关闭了连接".to_string()),
+                // Ping/Pong由tungstenite自动处理
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Some(format!("连接异常: {}", e)),
+                None => return Some("连接已断开".to_string()),
+            },
+        }
+    }
+}
+
+fn emit_state(app: &AppHandle, url: &str, state: SocketState, reason: Option<String>) {
+    let _ = app.emit("jarvis-ws-state", SocketStateEvent { state, url, reason });
+}
+
+// 连接并双向转发消息，连接断开后按退避间隔重连
+async fn run(
+    app: AppHandle,
+    url: String,
+    auth_token: Option<String>,
+    unix_socket: Option<PathBuf>,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+) {
+    let mut delay = MIN_RECONNECT_DELAY;
+    
+    loop {
+        emit_state(&app, &url, SocketState::Connecting, None);
+        let session = session(
+            &app,
+            &url,
+            auth_token.as_deref(),
+            unix_socket.as_deref(),
+            &mut outgoing,
+        )
+        .await;
+        let reason = match session {
+            Ok(None) => return,
+            Ok(Some(reason)) => {
+                delay = MIN_RECONNECT_DELAY;
+                reason
+            }
+            Err(e) => format!("连接失败: {}", e),
+        };
+        
+        emit_state(&app, &url, SocketState::Disconnected, Some(reason));
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+    }
+}
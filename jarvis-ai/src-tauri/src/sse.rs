@@ -0,0 +1,189 @@
+use serde::Serialize;
+use tauri::async_runtime::JoinHandle;
+use tokio::sync::OwnedSemaphorePermit;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{lock, AppState};
+
+// 部分服务以该数据帧表示流结束
+const DONE_MARKER: &str = "[DONE]";
+
+// jarvis-sse-chunk事件内容
+#[derive(Clone, Serialize)]
+struct ChunkEvent<'a> {
+    request_id: &'a str,
+    data: String,
+}
+
+// jarvis-sse-done事件内容，cancelled表示被cancel_sse_stream取消
+#[derive(Clone, Serialize)]
+struct DoneEvent<'a> {
+    request_id: &'a str,
+    cancelled: bool,
+}
+
+// jarvis-sse-error事件内容
+#[derive(Clone, Serialize)]
+struct ErrorEvent<'a> {
+    request_id: &'a str,
+    message: String,
+}
+
+// 按SSE格式拆分响应内容，返回每个事件的data字段(多行data以换行拼接)
+// 网络分块可能把多字节字符或\r\n拆开，因此按字节缓存，只解码完整的事件
+#[derive(Default)]
+pub struct SseParser {
+    buf: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buf.extend_from_slice(chunk);
+        
+        let mut events = Vec::new();
+        while let Some((end, next)) = find_event_end(&self.buf) {
+            let block: Vec<u8> = self.buf.drain(..next).take(end).collect();
+            let block = String::from_utf8_lossy(&block).replace("\r\n", "\n");
+            let data: Vec<&str> = block
+                .split(['\n', '\r'])
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
+
+// 查找第一个空行，返回事件内容的结束位置和空行之后的位置
+// 行尾可以是\r\n、\n或\r，缓冲区末尾的\r可能是被拆开的\r\n，等收到后续数据再判断
+fn find_event_end(buf: &[u8]) -> Option<(usize, usize)> {
+    let mut line_start = 0;
+    let mut index = 0;
+    while index < buf.len() {
+        let terminator = match buf[index] {
+            b'\n' => 1,
+            b'\r' if index + 1 == buf.len() => return None,
+            b'\r' if buf[index + 1] == b'\n' => 2,
+            b'\r' => 1,
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+        if index == line_start {
+            return Some((line_start, index + terminator));
+        }
+        index += terminator;
+        line_start = index;
+    }
+    None
+}
+
+// 在后台读取SSE响应，每个数据帧以jarvis-sse-chunk事件转发给前端
+// permit为并发请求名额，流结束或被取消时释放
+pub fn start(
+    app: AppHandle,
+    request: reqwest::RequestBuilder,
+    request_id: String,
+    permit: OwnedSemaphorePermit,
+) -> JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        let _permit = permit;
+        match run(&app, request, &request_id).await {
+            Ok(()) => {
+                let event = DoneEvent { request_id: &request_id, cancelled: false };
+                let _ = app.emit("jarvis-sse-done", event);
+            }
+            Err(message) => {
+                let event = ErrorEvent { request_id: &request_id, message };
+                let _ = app.emit("jarvis-sse-error", event);
+            }
+        }
+        lock(&app.state::<AppState>().sse_streams).remove(&request_id);
+    })
+}
+
+// 取消正在进行的流并通知前端
+pub fn cancel(app: &AppHandle, request_id: &str, task: JoinHandle<()>) {
+    task.abort();
+    let _ = app.emit("jarvis-sse-done", DoneEvent { request_id, cancelled: true });
+}
+
+async fn run(app: &AppHandle, request: reqwest::RequestBuilder, request_id: &str) -> Result<(), String> {
+    let mut response = request
+        .header(reqwest::header::ACCEPT, "text/event-stream")
+        .send()
+        .await
+        .map_err(|e| format!("连接JARVIS流式接口失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("JARVIS流式接口响应错误: {}", response.status()));
+    }
+    
+    let mut parser = SseParser::default();
+    loop {
+        let chunk = response
+            .chunk()
+            .await
+            .map_err(|e| format!("流式响应意外中断: {}", e))?;
+        let Some(chunk) = chunk else {
+            return Ok(());
+        };
+        
+        for data in parser.push(&chunk) {
+            if data == DONE_MARKER {
+                return Ok(());
+            }
+            let _ = app.emit("jarvis-sse-chunk", ChunkEvent { request_id, data });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 多个事件、多行data和注释行
+    #[test]
+    fn parses_events() {
+        let mut parser = SseParser::default();
+        let chunk = b": keep-alive\n\ndata: first\n\nevent: token\ndata:a\ndata: b\n\ndata: tail";
+        let events = parser.push(chunk);
+        assert_eq!(events, vec!["first", "a\nb"]);
+        assert_eq!(parser.push(b"\n\n"), vec!["tail"]);
+    }
+    
+    // 多字节字符和\r\n空行被拆到两个分块中时仍能正确解析
+    #[test]
+    fn handles_chunk_boundaries_inside_characters_and_crlf() {
+        let payload = "data: 你好，主人\r\n\r\ndata: 再见\r\n\r\n".as_bytes();
+        // 在"你"的第二个字节处拆开
+        let split_char = "data: ".len() + 2;
+        let mut parser = SseParser::default();
+        assert!(parser.push(&payload[..split_char]).is_empty());
+        assert_eq!(parser.push(&payload[split_char..]), vec!["你好，主人", "再见"]);
+        
+        // 在空行的\r和\n之间拆开
+        let split_crlf = "data: 你好，主人\r\n\r".len();
+        let mut parser = SseParser::default();
+        assert!(parser.push(&payload[..split_crlf]).is_empty());
+        assert_eq!(parser.push(&payload[split_crlf..]), vec!["你好，主人", "再见"]);
+        
+        // 在两个\r\n之间拆开
+        let split_lines = "data: 你好，主人\r\n".len();
+        let mut parser = SseParser::default();
+        assert!(parser.push(&payload[..split_lines]).is_empty());
+        assert_eq!(parser.push(&payload[split_lines..]), vec!["你好，主人", "再见"]);
+    }
+    
+    // 单独的\r也是合法的行尾，缓冲区末尾的\r要等后续数据才能确定是否属于\r\n
+    #[test]
+    fn accepts_bare_cr_line_endings() {
+        let mut parser = SseParser::default();
+        assert_eq!(parser.push(b"data: one\rdata: two\r\rdata: [DONE]\r"), vec!["one\ntwo"]);
+        assert!(parser.push(b"\r").is_empty());
+        assert_eq!(parser.push(b"\n"), vec![DONE_MARKER]);
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// JarvisStatus能识别的字段
+const KNOWN_FIELDS: &[&str] = &[
+    "version",
+    "uptime_secs",
+    "model",
+    "active_sessions",
+    "active_connections",
+    "memory_mb",
+];
+
+// JARVIS服务/status接口返回的状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JarvisStatus {
+    pub version: Option<String>,
+    pub uptime_secs: Option<u64>,
+    pub model: Option<String>,
+    #[serde(alias = "active_connections")]
+    pub active_sessions: Option<u32>,
+    pub memory_mb: Option<f64>,
+    // 返回内容与预期不符时保留原始JSON
+    #[serde(skip_deserializing)]
+    pub extra: Option<Value>,
+}
+
+impl JarvisStatus {
+    // 解析/status的返回内容，无法识别的字段或格式不会报错，而是原样放入extra
+    pub fn from_value(value: Value) -> Self {
+        let only_known_fields = value
+            .as_object()
+            .is_some_and(|object| object.keys().all(|key| KNOWN_FIELDS.contains(&key.as_str())));
+        
+        // 数组会被serde按位置填入字段，只解析对象，其余格式整体放入extra
+        let Some(mut fields) = value.as_object().cloned() else {
+            return JarvisStatus {
+                extra: Some(value),
+                ..Default::default()
+            };
+        };
+        // 同时包含active_sessions和它的别名时serde会报重复字段，以active_sessions为准
+        if fields.contains_key("active_sessions") {
+            fields.remove("active_connections");
+        }
+        
+        match serde_json::from_value::<JarvisStatus>(Value::Object(fields)) {
+            Ok(status) if only_known_fields => status,
+            Ok(status) => JarvisStatus {
+                extra: Some(value),
+                ..status
+            },
+            Err(_) => JarvisStatus {
+                extra: Some(value),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn known_fields_are_parsed_without_extra() {
+        let status = JarvisStatus::from_value(json!({
+            "version": "1.2.0",
+            "uptime_secs": 42,
+            "active_sessions": 3,
+        }));
+        
+        assert_eq!(status.version.as_deref(), Some("1.2.0"));
+        assert_eq!(status.uptime_secs, Some(42));
+        assert_eq!(status.active_sessions, Some(3));
+        assert_eq!(status.extra, None);
+    }
+    
+    // 无法识别的字段不影响已知字段，原始JSON保留在extra中
+    #[test]
+    fn unknown_keys_are_kept_in_extra() {
+        let value = json!({"model": "qwen", "gpu": "A100"});
+        let status = JarvisStatus::from_value(value.clone());
+        
+        assert_eq!(status.model.as_deref(), Some("qwen"));
+        assert_eq!(status.extra, Some(value));
+    }
+    
+    #[test]
+    fn non_object_is_kept_in_extra() {
+        let value = json!(["ok"]);
+        let status = JarvisStatus::from_value(value.clone());
+        
+        assert_eq!(status.version, None);
+        assert_eq!(status.extra, Some(value));
+    }
+    
+    #[test]
+    fn active_connections_alias_is_accepted() {
+        let status = JarvisStatus::from_value(json!({"active_connections": 5}));
+        
+        assert_eq!(status.active_sessions, Some(5));
+        assert_eq!(status.extra, None);
+    }
+    
+    // 同时返回两个字段时不能因重复字段丢掉其他已知字段
+    #[test]
+    fn active_sessions_wins_over_alias() {
+        let status = JarvisStatus::from_value(json!({
+            "version": "1.2.0",
+            "active_sessions": 2,
+            "active_connections": 9,
+        }));
+        
+        assert_eq!(status.version.as_deref(), Some("1.2.0"));
+        assert_eq!(status.active_sessions, Some(2));
+        assert_eq!(status.extra, None);
+    }
+}
@@ -0,0 +1,43 @@
+use std::path::Path;
+
+use crate::error::JarvisError;
+
+// 连接到指定Unix socket的HTTP客户端，请求URL中的主机名只用于Host头
+#[cfg(unix)]
+pub fn client(socket: &Path) -> reqwest::Client {
+    reqwest::Client::builder()
+        .unix_socket(socket)
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+// 非Unix系统无法切换为Unix socket传输(set_transport会拒绝)，不会调用到这里
+#[cfg(not(unix))]
+pub fn client(_socket: &Path) -> reqwest::Client {
+    reqwest::Client::new()
+}
+
+// 启动前检查socket文件：已有服务在监听时报错，残留的socket文件直接删除
+#[cfg(unix)]
+pub fn prepare_socket(path: &Path) -> Result<(), JarvisError> {
+    if !path.exists() {
+        return Ok(());
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(JarvisError::PortInUse(format!(
+            "Unix socket {}上已有服务在监听",
+            path.display()
+        )));
+    }
+    std::fs::remove_file(path)
+        .map_err(|e| JarvisError::Io(format!("删除残留的socket文件{}失败: {}", path.display(), e)))
+}
+
+#[cfg(not(unix))]
+pub fn prepare_socket(_path: &Path) -> Result<(), JarvisError> {
+    Err(unsupported())
+}
+
+pub fn unsupported() -> JarvisError {
+    JarvisError::NotSupported("当前系统不支持Unix socket，请使用TCP连接".to_string())
+}
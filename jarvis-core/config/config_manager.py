@@ -95,6 +95,12 @@ class ConfigManager:
         }
         
         # 加载配置
+        self.reload()
+        
+        logger.info("配置管理器初始化完成")
+    
+    def reload(self):
+        """从配置文件重新加载全部配置"""
         self.system_config = self._load_config(
             self.system_config_path, self.default_system_config
         )
@@ -104,8 +110,6 @@ class ConfigManager:
         self.personality_config = self._load_config(
             self.personality_config_path, self.default_personality_config
         )
-        
-        logger.info("配置管理器初始化完成")
     
     def _load_config(self, config_path: Path, default_config: Dict[str, Any]) -> Dict[str, Any]:
         """加载配置文件"""
@@ -6,6 +6,7 @@ JARVIS AI Core - FastAPI主服务器
 import asyncio
 import logging
 import json
+import signal
 import sys
 import os
 from contextlib import asynccontextmanager
@@ -39,6 +40,26 @@ memory_manager = None
 config_manager = None
 speech_service = None
 
+# 防止信号和/reload接口同时触发重新加载
+reload_lock = asyncio.Lock()
+
+async def reload_core():
+    """重新加载配置和模型客户端，记忆和已有连接保持不变"""
+    async with reload_lock:
+        logger.info("正在重新加载JARVIS核心配置和模型...")
+        if config_manager:
+            config_manager.reload()
+        if model_router:
+            await model_router.initialize()
+        logger.info("JARVIS核心重新加载完成")
+
+async def reload_from_signal():
+    """处理SIGHUP，重新加载失败时只记录日志，服务继续运行"""
+    try:
+        await reload_core()
+    except Exception as e:
+        logger.error(f"重新加载失败: {e}")
+
 @asynccontextmanager
 async def lifespan(app: FastAPI):
     """应用生命周期管理"""
@@ -76,6 +97,12 @@ async def lifespan(app: FastAPI):
     
     logger.info("JARVIS核心服务初始化完成!")
     
+    # 收到SIGHUP时重新加载而不是退出(仅Unix)
+    if hasattr(signal, "SIGHUP"):
+        asyncio.get_running_loop().add_signal_handler(
+            signal.SIGHUP, lambda: asyncio.ensure_future(reload_from_signal())
+        )
+    
     yield
     
     # 关闭时清理
@@ -149,6 +176,16 @@ async def get_status():
         "active_connections": len(manager.active_connections)
     }
 
+@app.post("/reload")
+async def reload():
+    """重新加载配置和模型，供无法发送信号的系统使用"""
+    try:
+        await reload_core()
+        return {"status": "reloaded"}
+    except Exception as e:
+        logger.error(f"重新加载失败: {e}")
+        return JSONResponse(status_code=500, content={"error": f"重新加载失败: {e}"})
+
 @app.post("/chat")
 async def chat(request: Dict[str, Any]):
     """处理聊天请求"""
@@ -385,10 +422,14 @@ async def speech_websocket_endpoint(websocket: WebSocket):
             pass
 
 if __name__ == "__main__":
+    # 桌面端把SIGHUP发给整个进程组，reload模式下的监控进程忽略该信号，由服务进程处理
+    if hasattr(signal, "SIGHUP"):
+        signal.signal(signal.SIGHUP, signal.SIG_IGN)
     uvicorn.run(
         "main:app",
-        host="127.0.0.1",
-        port=8000,
+        host=os.environ.get("JARVIS_HOST", "127.0.0.1"),
+        port=int(os.environ.get("JARVIS_PORT", "8000")),
+        uds=os.environ.get("JARVIS_UDS"),
         reload=True,
         log_level="info"
     )
\ No newline at end of file